@@ -6,7 +6,8 @@ use std::io::BufReader;
 use std::io::Error;
 use std::path::PathBuf;
 use structopt::StructOpt;
-use tsparser_parser::parser::Parser;
+use tsparser_parser::ast::Program;
+use tsparser_parser::parser::{EcmaVersion, ParseError, Parser, ParserOptions, SourceType};
 use tsparser_parser::tokenizer::Tokenizer;
 use utf8_chars::BufReadCharsExt;
 
@@ -19,27 +20,98 @@ struct Opt {
     /// Tokenize and print out tokens, but don't parse.
     #[structopt(short, long)]
     tokenize_only: bool,
+    /// Recover from syntax errors and print out all of them after the AST.
+    #[structopt(short, long)]
+    recover: bool,
+    /// Record comments and attach them to the statements next to them.
+    #[structopt(short, long)]
+    comments: bool,
+    /// Parse the input as a module. This is the default for `.mjs` files.
+    #[structopt(long, conflicts_with = "script")]
+    module: bool,
+    /// Parse the input as a script. This is the default for all files
+    /// other than `.mjs` files.
+    #[structopt(long)]
+    script: bool,
+    /// ECMAScript version to target, for example, `es2020` or `latest`.
+    #[structopt(long, default_value = "latest")]
+    ecma_version: EcmaVersion,
+    /// Disallow TypeScript syntax.
+    #[structopt(long)]
+    no_typescript: bool,
+    /// Allow JSX syntax.
+    #[structopt(long)]
+    jsx: bool,
+    /// Parse scripts as strict mode code.
+    #[structopt(long)]
+    strict: bool,
+    /// Allow `return` statements outside of functions.
+    #[structopt(long)]
+    allow_return_outside_function: bool,
+}
+
+impl Opt {
+    /// Returns true if the input should be parsed as a module.
+    fn is_module(&self) -> bool {
+        if self.module || self.script {
+            return self.module;
+        }
+        self.input.extension().is_some_and(|ext| ext == "mjs")
+    }
+
+    fn parser_options(&self) -> ParserOptions {
+        let source_type = if self.is_module() {
+            SourceType::Module
+        } else {
+            SourceType::Script
+        };
+        ParserOptions::new()
+            .preserve_comments(self.comments)
+            .source_type(source_type)
+            .ecma_version(self.ecma_version)
+            .typescript(!self.no_typescript)
+            .jsx(self.jsx)
+            .strict(self.strict)
+            .allow_return_outside_function(self.allow_return_outside_function)
+            .recover(self.recover)
+    }
 }
 
 fn main() -> Result<(), Error> {
     let opt = Opt::from_args();
-    let input = File::open(opt.input)?;
+    let input = File::open(&opt.input)?;
     let mut input = BufReader::new(input);
     let mut tokenizer = Tokenizer::new(input.chars());
     if opt.tokenize_only {
         while let Some(token) = tokenizer.next_token() {
-            println!("{:?} => `{}`", token, tokenizer.slice());
+            println!(
+                "{:?} => `{}` {:?}",
+                token,
+                tokenizer.slice(),
+                tokenizer.span()
+            );
         }
         return Ok(());
     }
-    let mut parser = Parser::new(tokenizer);
-    match parser.parse_script() {
-        Ok(ast) => {
-            println!("{:#?}", ast);
-        }
-        Err(e) => {
-            println!("Parse error: {}", e.reason)
-        }
+    let mut parser = Parser::with_options(tokenizer, opt.parser_options());
+    let (program, errors) = parser.parse();
+    match program {
+        Some(Program::Module(ast)) => println!("{:#?}", ast),
+        Some(Program::Script(ast)) => println!("{:#?}", ast),
+        None => {}
+    }
+    for e in &errors {
+        print_error(e);
     }
     Ok(())
 }
+
+fn print_error(e: &ParseError) {
+    println!(
+        "Parse error: {} [{}] at {}:{}",
+        e,
+        e.code(),
+        e.span.start.line,
+        e.span.start.column
+    )
+}
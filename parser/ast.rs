@@ -2,30 +2,796 @@
 // SPDX-License-Identifier: MIT
 
 //! ECMAScript Abstract Syntax Tree (AST)
+//!
+//! Every AST node records its location in the source text as a `Span`.
+
+use crate::span::Span;
+
+/// A script or a module.
+#[derive(Debug)]
+pub enum Program {
+    Module(Module),
+    Script(Script),
+}
 
 /// A script.
 #[derive(Debug)]
 pub struct Script {
     pub body: BlockStatement,
+    /// The comments in the script, in source order.
+    pub comments: Vec<Comment>,
+    pub span: Span,
 }
 
 impl Script {
-    pub fn new(body: BlockStatement) -> Self {
-        Self { body }
+    pub fn new(body: BlockStatement, comments: Vec<Comment>, span: Span) -> Self {
+        Self {
+            body,
+            comments,
+            span,
+        }
     }
 }
 
-// An identifier.
+/// A module.
+#[derive(Debug)]
+pub struct Module {
+    pub body: Vec<ModuleItem>,
+    /// The comments in the module, in source order.
+    pub comments: Vec<Comment>,
+    pub span: Span,
+}
+
+impl Module {
+    pub fn new(body: Vec<ModuleItem>, comments: Vec<Comment>, span: Span) -> Self {
+        Self {
+            body,
+            comments,
+            span,
+        }
+    }
+}
+
+/// A comment.
+#[derive(Debug)]
+pub struct Comment {
+    pub kind: CommentKind,
+    /// The text of the comment without the delimiters. For example, the
+    /// text of `/* a */` is ` a `.
+    pub text: String,
+    /// The node that this comment is attached to, if any.
+    pub attachment: Option<CommentAttachment>,
+    pub span: Span,
+}
+
+impl Comment {
+    /// Returns true if this comment contains a line terminator.
+    ///
+    /// A multi-line comment that contains a line terminator separates the
+    /// tokens around it like a line terminator does, for example, for
+    /// automatic semicolon insertion.
+    pub fn has_line_terminator(&self) -> bool {
+        self.span.start.line != self.span.end.line
+    }
+}
+
+/// The kind of a comment.
+#[derive(Debug, PartialEq)]
+pub enum CommentKind {
+    /// A `/* ... */` comment.
+    MultiLine,
+    /// A `// ...` comment.
+    SingleLine,
+}
+
+/// The node that a comment is attached to, identified by its span.
+///
+/// Comments are attached to statements and module items. A comment is
+/// leading if it comes before the node, with nothing but other comments
+/// between them, and trailing if it follows the node on the same line as
+/// the node ends. Comments elsewhere, such as inside expressions, are not
+/// attached to any node.
+#[derive(Debug, PartialEq)]
+pub enum CommentAttachment {
+    Leading(Span),
+    Trailing(Span),
+}
+
+/// An item in the body of a module.
+///
+/// Import and export declarations may only appear at the top level of a
+/// module, which is why they are not statements.
 #[derive(Debug)]
+pub enum ModuleItem {
+    ExportDeclaration(ExportDeclaration),
+    ImportDeclaration(ImportDeclaration),
+    Statement(Statement),
+}
+
+impl ModuleItem {
+    /// Returns the source location of this module item.
+    pub fn span(&self) -> Span {
+        match self {
+            ModuleItem::ExportDeclaration(decl) => decl.span(),
+            ModuleItem::ImportDeclaration(decl) => decl.span,
+            ModuleItem::Statement(stmt) => stmt.span(),
+        }
+    }
+}
+
+// An identifier.
+#[derive(Clone, Debug)]
 pub struct Identifier {
     pub value: String,
+    pub span: Span,
 }
 
 // An expression.
 #[derive(Debug)]
 pub enum Expression {
+    /// An array literal.
+    ArrayExpression(ArrayExpression),
+    /// An arrow function.
+    ArrowFunctionExpression(Box<ArrowFunctionExpression>),
+    /// An assignment expression.
+    AssignmentExpression(Box<AssignmentExpression>),
+    /// An `await` expression.
+    AwaitExpression(Box<AwaitExpression>),
+    /// A BigInt literal.
+    BigIntLiteral(BigIntLiteral),
     /// A binary expression.
     BinaryExpression(Box<BinaryExpression>),
+    /// A boolean literal, `true` or `false`.
+    BooleanLiteral(BooleanLiteral),
+    /// A call expression.
+    CallExpression(Box<CallExpression>),
+    /// An optional chain.
+    ChainExpression(Box<ChainExpression>),
+    /// A class expression.
+    ClassExpression(Box<Class>),
+    /// A conditional expression.
+    ConditionalExpression(Box<ConditionalExpression>),
+    /// A placeholder for an expression that failed to parse.
+    Error(Span),
+    /// A function expression.
+    FunctionExpression(Box<Function>),
+    /// An identifier reference.
+    Identifier(Identifier),
+    /// A dynamic import.
+    ImportExpression(Box<ImportExpression>),
+    /// A property access.
+    MemberExpression(Box<MemberExpression>),
+    /// A meta property, `new.target` or `import.meta`.
+    MetaProperty(MetaProperty),
+    /// A `new` expression.
+    NewExpression(Box<NewExpression>),
+    /// The `null` literal.
+    NullLiteral(Span),
+    /// A numeric literal.
+    NumericLiteral(NumericLiteral),
+    /// An object literal.
+    ObjectExpression(ObjectExpression),
+    /// A parenthesized expression.
+    ParenthesizedExpression(Box<ParenthesizedExpression>),
+    /// A regular expression literal.
+    RegExpLiteral(RegExpLiteral),
+    /// A sequence of comma-separated expressions.
+    SequenceExpression(SequenceExpression),
+    /// A string literal.
+    StringLiteral(StringLiteral),
+    /// The `super` keyword, which is the object of a property access or
+    /// the callee of a call.
+    Super(Span),
+    /// A tagged template.
+    TaggedTemplateExpression(Box<TaggedTemplateExpression>),
+    /// A template literal.
+    TemplateLiteral(TemplateLiteral),
+    /// The `this` keyword.
+    ThisExpression(Span),
+    /// A unary expression.
+    UnaryExpression(Box<UnaryExpression>),
+    /// An increment or decrement expression.
+    UpdateExpression(Box<UpdateExpression>),
+    /// A `yield` expression.
+    YieldExpression(Box<YieldExpression>),
+}
+
+impl Expression {
+    /// Returns the source location of this expression.
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::ArrayExpression(expr) => expr.span,
+            Expression::ArrowFunctionExpression(expr) => expr.span,
+            Expression::AssignmentExpression(expr) => expr.span,
+            Expression::AwaitExpression(expr) => expr.span,
+            Expression::BigIntLiteral(expr) => expr.span,
+            Expression::BinaryExpression(expr) => expr.span,
+            Expression::BooleanLiteral(expr) => expr.span,
+            Expression::CallExpression(expr) => expr.span,
+            Expression::ChainExpression(expr) => expr.span,
+            Expression::ClassExpression(expr) => expr.span,
+            Expression::ConditionalExpression(expr) => expr.span,
+            Expression::Error(span)
+            | Expression::NullLiteral(span)
+            | Expression::Super(span)
+            | Expression::ThisExpression(span) => *span,
+            Expression::FunctionExpression(expr) => expr.span,
+            Expression::Identifier(expr) => expr.span,
+            Expression::ImportExpression(expr) => expr.span,
+            Expression::MemberExpression(expr) => expr.span,
+            Expression::MetaProperty(expr) => expr.span,
+            Expression::NewExpression(expr) => expr.span,
+            Expression::NumericLiteral(expr) => expr.span,
+            Expression::ObjectExpression(expr) => expr.span,
+            Expression::ParenthesizedExpression(expr) => expr.span,
+            Expression::RegExpLiteral(expr) => expr.span,
+            Expression::SequenceExpression(expr) => expr.span,
+            Expression::StringLiteral(expr) => expr.span,
+            Expression::TaggedTemplateExpression(expr) => expr.span,
+            Expression::TemplateLiteral(expr) => expr.span,
+            Expression::UnaryExpression(expr) => expr.span,
+            Expression::UpdateExpression(expr) => expr.span,
+            Expression::YieldExpression(expr) => expr.span,
+        }
+    }
+}
+
+/// An array literal. For example, `[a, , ...b]`.
+#[derive(Debug)]
+pub struct ArrayExpression {
+    /// The elements of the literal. A hole, such as the second element of
+    /// `[a, , b]`, is `None`.
+    pub elements: Vec<Option<ExpressionOrSpread>>,
+    pub span: Span,
+}
+
+/// An expression or a spread element, which is an element of an array
+/// literal or an argument of a call.
+#[derive(Debug)]
+pub enum ExpressionOrSpread {
+    Expression(Expression),
+    SpreadElement(SpreadElement),
+}
+
+/// A spread element, which spreads the values of an iterable or the
+/// properties of an object. For example, `...a`.
+#[derive(Debug)]
+pub struct SpreadElement {
+    pub argument: Expression,
+    pub span: Span,
+}
+
+/// An object literal. For example, `{ a, b: 1, c() {}, ...d }`.
+#[derive(Debug)]
+pub struct ObjectExpression {
+    pub properties: Vec<ObjectProperty>,
+    pub span: Span,
+}
+
+/// A property of an object literal.
+#[derive(Debug)]
+pub enum ObjectProperty {
+    /// A shorthand property with an initializer, for example, `a = 1`,
+    /// which is only valid if the object literal is reinterpreted as a
+    /// destructuring pattern.
+    CoverInitializedName(PatternProperty),
+    /// A method, getter or setter. For example, `c() {}` or `get d() {}`.
+    /// It is never static and never a constructor.
+    MethodDefinition(MethodDefinition),
+    /// A property. For example, `b: 1` or `a`.
+    Property(Property),
+    /// A spread property. For example, `...d`.
+    SpreadElement(SpreadElement),
+}
+
+impl ObjectProperty {
+    /// Returns the source location of this property.
+    pub fn span(&self) -> Span {
+        match self {
+            ObjectProperty::CoverInitializedName(property) => property.span,
+            ObjectProperty::MethodDefinition(property) => property.span,
+            ObjectProperty::Property(property) => property.span,
+            ObjectProperty::SpreadElement(property) => property.span,
+        }
+    }
+}
+
+/// A property of an object literal that has a value.
+#[derive(Debug)]
+pub struct Property {
+    pub key: PropertyKey,
+    pub value: Expression,
+    /// Whether the property is written in the shorthand form, for example,
+    /// `a`, where the key is also the identifier reference of the value.
+    pub shorthand: bool,
+    pub span: Span,
+}
+
+/// An assignment expression. For example, `a = 1`, `[a, b] = c` or
+/// `a.b += 1`.
+#[derive(Debug)]
+pub struct AssignmentExpression {
+    pub op: AssignmentOp,
+    /// The assignment target, which can only be a destructuring pattern if
+    /// the operator is `=`.
+    pub left: Pattern,
+    pub right: Expression,
+    pub span: Span,
+}
+
+/// An assignment operator.
+#[derive(Debug)]
+pub enum AssignmentOp {
+    AdditionAssignment,
+    Assignment,
+    BitwiseAndAssignment,
+    BitwiseOrAssignment,
+    BitwiseXorAssignment,
+    DivisionAssignment,
+    ExponentiationAssignment,
+    LeftShiftAssignment,
+    LogicalAndAssignment,
+    LogicalNullishAssignment,
+    LogicalOrAssignment,
+    MultiplicationAssignment,
+    RemainderAssignment,
+    RightShiftAssignment,
+    SubtractionAssignment,
+    UnsignedRightShiftAssignment,
+}
+
+/// A conditional expression. For example, `a ? b : c`.
+#[derive(Debug)]
+pub struct ConditionalExpression {
+    pub test: Expression,
+    pub consequent: Expression,
+    pub alternate: Expression,
+    pub span: Span,
+}
+
+/// A sequence of comma-separated expressions. For example, `a, b`.
+#[derive(Debug)]
+pub struct SequenceExpression {
+    pub expressions: Vec<Expression>,
+    pub span: Span,
+}
+
+/// A unary expression. For example, `typeof a` or `-a`.
+#[derive(Debug)]
+pub struct UnaryExpression {
+    pub op: UnaryOp,
+    pub argument: Expression,
+    pub span: Span,
+}
+
+/// A unary operator.
+#[derive(Debug)]
+pub enum UnaryOp {
+    BitwiseNot,
+    Delete,
+    LogicalNot,
+    Minus,
+    Plus,
+    Typeof,
+    Void,
+}
+
+/// An increment or decrement expression. For example, `++a` or `a--`.
+#[derive(Debug)]
+pub struct UpdateExpression {
+    pub op: UpdateOp,
+    /// Whether the operator comes before the argument.
+    pub prefix: bool,
+    pub argument: Expression,
+    pub span: Span,
+}
+
+/// An update operator.
+#[derive(Debug)]
+pub enum UpdateOp {
+    Decrement,
+    Increment,
+}
+
+/// A call expression. For example, `a(b, ...c)` or `a?.(b)`.
+#[derive(Debug)]
+pub struct CallExpression {
+    pub callee: Expression,
+    pub arguments: Vec<ExpressionOrSpread>,
+    /// Whether the call is optional, as in `a?.(b)`.
+    pub optional: bool,
+    pub span: Span,
+}
+
+/// A property access. For example, `a.b`, `a[b]` or `a?.#b`.
+#[derive(Debug)]
+pub struct MemberExpression {
+    pub object: Expression,
+    pub property: MemberProperty,
+    /// Whether the property access is optional, as in `a?.b`.
+    pub optional: bool,
+    pub span: Span,
+}
+
+/// The property of a property access.
+#[derive(Debug)]
+pub enum MemberProperty {
+    /// A computed property. For example, `b` in `a[b]`.
+    Computed(Expression),
+    /// An identifier name, which can also be a reserved word.
+    Identifier(Identifier),
+    /// A private name. The value of the identifier is the name without the
+    /// `#`.
+    PrivateIdentifier(Identifier),
+}
+
+/// An optional chain, which contains at least one optional property access
+/// or call. For example, `a?.b.c()`.
+///
+/// If the object of an optional access is `null` or `undefined`, the whole
+/// chain evaluates to `undefined`.
+#[derive(Debug)]
+pub struct ChainExpression {
+    pub expression: Expression,
+    pub span: Span,
+}
+
+/// A `new` expression. For example, `new A(b)` or `new A`.
+#[derive(Debug)]
+pub struct NewExpression {
+    pub callee: Expression,
+    /// The arguments, which are empty if the parentheses are omitted.
+    pub arguments: Vec<ExpressionOrSpread>,
+    pub span: Span,
+}
+
+/// A meta property, which is `new.target` or `import.meta`.
+#[derive(Debug)]
+pub struct MetaProperty {
+    pub meta: Identifier,
+    pub property: Identifier,
+    pub span: Span,
+}
+
+/// A dynamic import. For example, `import("a", { with: { type: "json" } })`.
+#[derive(Debug)]
+pub struct ImportExpression {
+    pub source: Expression,
+    pub options: Option<Expression>,
+    pub span: Span,
+}
+
+/// An `await` expression.
+#[derive(Debug)]
+pub struct AwaitExpression {
+    pub argument: Expression,
+    pub span: Span,
+}
+
+/// A `yield` expression. For example, `yield a` or `yield* a`.
+#[derive(Debug)]
+pub struct YieldExpression {
+    pub argument: Option<Expression>,
+    /// Whether the expression is `yield*`, which yields each value of the
+    /// argument.
+    pub delegate: bool,
+    pub span: Span,
+}
+
+/// A boolean literal, `true` or `false`.
+#[derive(Debug)]
+pub struct BooleanLiteral {
+    pub value: bool,
+    pub span: Span,
+}
+
+/// A numeric literal.
+#[derive(Debug)]
+pub struct NumericLiteral {
+    /// The value of the numeric literal.
+    pub value: f64,
+    /// The source text of the numeric literal.
+    pub raw: String,
+    pub span: Span,
+}
+
+/// A BigInt literal. For example, `0x1fn`.
+#[derive(Debug)]
+pub struct BigIntLiteral {
+    /// The digits of the literal, including a radix prefix such as `0x`, but
+    /// without numeric separators and the `n` suffix.
+    pub digits: String,
+    pub span: Span,
+}
+
+/// A regular expression literal. For example, `/ab+c/gi`.
+#[derive(Debug)]
+pub struct RegExpLiteral {
+    /// The source text of the pattern, between the slashes.
+    pub pattern: String,
+    /// The flags after the closing slash.
+    pub flags: String,
+    pub span: Span,
+}
+
+/// A string literal.
+#[derive(Debug)]
+pub struct StringLiteral {
+    /// The value of the string, with escape sequences decoded.
+    pub value: String,
+    /// The source text of the string literal, including the quotes.
+    pub raw: String,
+    pub span: Span,
+}
+
+/// A template literal. For example, `` `a${b}c` ``.
+#[derive(Debug)]
+pub struct TemplateLiteral {
+    /// The string parts of the template. There is always one more string
+    /// part than there are substitutions.
+    pub quasis: Vec<TemplateElement>,
+    /// The substitutions of the template, which come between the string
+    /// parts.
+    pub expressions: Vec<Expression>,
+    pub span: Span,
+}
+
+/// A string part of a template literal.
+///
+/// The span of a template element includes its delimiters, for example,
+/// `` `a${ `` and `` }c` ``.
+#[derive(Debug)]
+pub struct TemplateElement {
+    /// The value of the string part, with escape sequences decoded. An
+    /// invalid escape sequence, which is allowed only in tagged templates,
+    /// leaves the value undefined.
+    pub cooked: Option<String>,
+    /// The source text of the string part, with line terminators
+    /// normalized to LF.
+    pub raw: String,
+    pub span: Span,
+}
+
+/// A tagged template. For example, `` tag`a${b}c` ``.
+#[derive(Debug)]
+pub struct TaggedTemplateExpression {
+    pub tag: Expression,
+    pub quasi: TemplateLiteral,
+    pub span: Span,
+}
+
+/// A function declaration or a function expression. For example,
+/// `async function f(a, b = 1) {}`.
+#[derive(Debug)]
+pub struct Function {
+    /// The name of the function, which is optional in function expressions
+    /// and in `export default` declarations.
+    pub id: Option<Identifier>,
+    pub params: Vec<Pattern>,
+    pub body: BlockStatement,
+    /// Whether this is an `async` function.
+    pub is_async: bool,
+    /// Whether this is a generator function, `function*`.
+    pub is_generator: bool,
+    pub span: Span,
+}
+
+/// An arrow function. For example, `(a, b) => a + b`.
+#[derive(Debug)]
+pub struct ArrowFunctionExpression {
+    pub params: Vec<Pattern>,
+    pub body: ArrowFunctionBody,
+    /// Whether this is an `async` arrow function.
+    pub is_async: bool,
+    pub span: Span,
+}
+
+/// The body of an arrow function.
+#[derive(Debug)]
+pub enum ArrowFunctionBody {
+    /// A block body. For example, `{ return a; }` in `a => { return a; }`.
+    Block(BlockStatement),
+    /// A concise body, which is an expression. For example, `a + 1` in
+    /// `a => a + 1`.
+    Expression(Expression),
+}
+
+/// A binding pattern, which binds the identifiers in it, for example, in a
+/// parameter list or a variable declaration. Assignment targets, such as the
+/// left-hand side of `for (a of b) {}`, are patterns, too.
+#[derive(Debug)]
+pub enum Pattern {
+    /// An array destructuring pattern.
+    ArrayPattern(ArrayPattern),
+    /// A pattern with a default value.
+    AssignmentPattern(Box<AssignmentPattern>),
+    /// A binding identifier.
+    Identifier(Identifier),
+    /// A property access, which is only valid in assignment targets. For
+    /// example, `a.b` in `[a.b] = c`.
+    MemberExpression(Box<MemberExpression>),
+    /// An object destructuring pattern.
+    ObjectPattern(ObjectPattern),
+    /// A rest element.
+    RestElement(Box<RestElement>),
+}
+
+impl Pattern {
+    /// Returns the source location of this pattern.
+    pub fn span(&self) -> Span {
+        match self {
+            Pattern::ArrayPattern(pattern) => pattern.span,
+            Pattern::AssignmentPattern(pattern) => pattern.span,
+            Pattern::Identifier(pattern) => pattern.span,
+            Pattern::MemberExpression(pattern) => pattern.span,
+            Pattern::ObjectPattern(pattern) => pattern.span,
+            Pattern::RestElement(pattern) => pattern.span,
+        }
+    }
+}
+
+/// An array destructuring pattern. For example, `[a, , ...b]`.
+#[derive(Debug)]
+pub struct ArrayPattern {
+    /// The elements of the pattern. A hole, such as the second element of
+    /// `[a, , b]`, is `None`.
+    pub elements: Vec<Option<Pattern>>,
+    pub span: Span,
+}
+
+/// An object destructuring pattern. For example, `{ a, b: c, ...d }`.
+#[derive(Debug)]
+pub struct ObjectPattern {
+    pub properties: Vec<ObjectPatternProperty>,
+    pub span: Span,
+}
+
+/// A property of an object destructuring pattern.
+#[derive(Debug)]
+pub enum ObjectPatternProperty {
+    /// A property. For example, `b: c` or `a = 1`.
+    Property(PatternProperty),
+    /// A rest property. For example, `...d`.
+    RestElement(RestElement),
+}
+
+/// A property of an object destructuring pattern that binds the value of
+/// the property with the key to the pattern.
+#[derive(Debug)]
+pub struct PatternProperty {
+    pub key: PropertyKey,
+    pub value: Pattern,
+    /// Whether the property is written in the shorthand form, for example,
+    /// `a` or `a = 1`, where the key is also the binding identifier.
+    pub shorthand: bool,
+    pub span: Span,
+}
+
+/// The key of a property.
+#[derive(Debug)]
+pub enum PropertyKey {
+    /// A computed key. For example, `[a + b]`.
+    Computed(Expression),
+    /// An identifier name, which can also be a reserved word.
+    Identifier(Identifier),
+    NumericLiteral(NumericLiteral),
+    /// A private name of a class member, such as `#a`. The value of the
+    /// identifier is the name without the `#`.
+    PrivateIdentifier(Identifier),
+    StringLiteral(StringLiteral),
+}
+
+impl PropertyKey {
+    /// Returns the source location of this key, which excludes the brackets
+    /// of a computed key.
+    pub fn span(&self) -> Span {
+        match self {
+            PropertyKey::Computed(key) => key.span(),
+            PropertyKey::Identifier(key) => key.span,
+            PropertyKey::NumericLiteral(key) => key.span,
+            PropertyKey::PrivateIdentifier(key) => key.span,
+            PropertyKey::StringLiteral(key) => key.span,
+        }
+    }
+}
+
+/// A class declaration or a class expression. For example,
+/// `class A extends B { c = 1; }`.
+#[derive(Debug)]
+pub struct Class {
+    /// The name of the class, which is optional in class expressions and in
+    /// `export default` declarations.
+    pub id: Option<Identifier>,
+    /// The expression after `extends`.
+    pub super_class: Option<Expression>,
+    pub body: Vec<ClassElement>,
+    pub span: Span,
+}
+
+/// An element of a class body.
+#[derive(Debug)]
+pub enum ClassElement {
+    /// An auto-accessor, which is a field with a getter and a setter. For
+    /// example, `accessor a = 1;`.
+    AccessorProperty(PropertyDefinition),
+    /// A constructor, method, getter or setter.
+    MethodDefinition(MethodDefinition),
+    /// A field. For example, `a = 1;` or `static #b;`.
+    PropertyDefinition(PropertyDefinition),
+    /// A static initialization block. For example, `static { a(); }`.
+    StaticBlock(BlockStatement),
+}
+
+impl ClassElement {
+    /// Returns the source location of this class element.
+    pub fn span(&self) -> Span {
+        match self {
+            ClassElement::AccessorProperty(element) => element.span,
+            ClassElement::MethodDefinition(element) => element.span,
+            ClassElement::PropertyDefinition(element) => element.span,
+            ClassElement::StaticBlock(element) => element.span,
+        }
+    }
+}
+
+/// A constructor, method, getter or setter of a class, or a method, getter
+/// or setter of an object literal.
+#[derive(Debug)]
+pub struct MethodDefinition {
+    pub key: PropertyKey,
+    pub kind: MethodKind,
+    /// The function that implements the method. Its span starts at the
+    /// parameter list.
+    pub value: Function,
+    pub is_static: bool,
+    pub span: Span,
+}
+
+/// The kind of a method definition.
+#[derive(Debug, PartialEq)]
+pub enum MethodKind {
+    Constructor,
+    Get,
+    Method,
+    Set,
+}
+
+/// A field or an auto-accessor of a class.
+#[derive(Debug)]
+pub struct PropertyDefinition {
+    pub key: PropertyKey,
+    /// The initializer of the field, if any.
+    pub value: Option<Expression>,
+    pub is_static: bool,
+    pub span: Span,
+}
+
+/// A pattern with a default value, which is used when the value to bind is
+/// `undefined`. For example, `a = 1`.
+#[derive(Debug)]
+pub struct AssignmentPattern {
+    pub left: Pattern,
+    pub right: Expression,
+    pub span: Span,
+}
+
+/// A rest element, which binds the rest of the values to the pattern. For
+/// example, `...a`.
+#[derive(Debug)]
+pub struct RestElement {
+    pub argument: Pattern,
+    pub span: Span,
+}
+
+/// A parenthesized expression.
+///
+/// The parentheses are retained in the AST because some early errors, such
+/// as mixing `??` with `||`, depend on whether an expression was
+/// parenthesized.
+#[derive(Debug)]
+pub struct ParenthesizedExpression {
+    pub expression: Expression,
+    pub span: Span,
 }
 
 /// A binary expression.
@@ -41,6 +807,7 @@ pub struct BinaryExpression {
     pub op: BinaryOp,
     /// Right side of this binary expression.
     pub right: Expression,
+    pub span: Span,
 }
 
 /// A binary operator.
@@ -55,13 +822,16 @@ pub enum BinaryOp {
     Exponentiation,
     GreaterThan,
     GreaterThanOrEqual,
+    In,
     Inequality,
+    InstanceOf,
     LeftShift,
     LessThan,
     LessThanOrEqual,
     LogicalAnd,
     LogicalOr,
     Multiplication,
+    NullishCoalescing,
     Remainder,
     RightSift,
     StrictEquality,
@@ -71,24 +841,230 @@ pub enum BinaryOp {
 }
 
 /// A statement or a declaration.
+///
+/// `DebuggerStatement`, `EmptyStatement` and `Error` have no parts other than
+/// their source location, so they only record a span.
 #[derive(Debug)]
 pub enum Statement {
-    BlockStatement(BlockStatement),       // Block statement
-    BreakStatement,                       // `break` statement
-    Comment,                              // Comment.
-    ContinueStatement,                    // `continue` statement
-    Expressiontatement,                   // Expression statement
-    ForStatement,                         // `for` statement
-    FunctionDeclaration,                  // Function declaration
-    IfStatement,                          // `if` statement
-    ImportDeclaration(ImportDeclaration), // `import` declaration
-    VariableStatement(VariableStatement), // Variable statement
+    BlockStatement(BlockStatement),           // Block statement
+    BreakStatement(BreakStatement),           // `break` statement
+    ClassDeclaration(Class),                  // Class declaration
+    ContinueStatement(ContinueStatement),     // `continue` statement
+    DebuggerStatement(Span),                  // `debugger` statement
+    DoWhileStatement(Box<DoWhileStatement>),  // `do`-`while` statement
+    EmptyStatement(Span),                     // Empty statement, `;`
+    Error(Span),                              // Statement that failed to parse
+    ExpressionStatement(ExpressionStatement), // Expression statement
+    ForInStatement(Box<ForInStatement>),      // `for`-`in` statement
+    ForOfStatement(Box<ForOfStatement>),      // `for`-`of` statement
+    ForStatement(Box<ForStatement>),          // `for` statement
+    FunctionDeclaration(Function),            // Function declaration
+    IfStatement(Box<IfStatement>),            // `if` statement
+    LabeledStatement(Box<LabeledStatement>),  // Labeled statement
+    ReturnStatement(ReturnStatement),         // `return` statement
+    SwitchStatement(SwitchStatement),         // `switch` statement
+    ThrowStatement(ThrowStatement),           // `throw` statement
+    TryStatement(TryStatement),               // `try` statement
+    VariableStatement(VariableStatement),     // Variable statement
+    WhileStatement(Box<WhileStatement>),      // `while` statement
+    WithStatement(Box<WithStatement>),        // `with` statement
+}
+
+impl Statement {
+    /// Returns the source location of this statement.
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::BlockStatement(stmt) => stmt.span,
+            Statement::BreakStatement(stmt) => stmt.span,
+            Statement::ClassDeclaration(stmt) => stmt.span,
+            Statement::ContinueStatement(stmt) => stmt.span,
+            Statement::DebuggerStatement(span)
+            | Statement::EmptyStatement(span)
+            | Statement::Error(span) => *span,
+            Statement::DoWhileStatement(stmt) => stmt.span,
+            Statement::ExpressionStatement(stmt) => stmt.span,
+            Statement::ForInStatement(stmt) => stmt.span,
+            Statement::ForOfStatement(stmt) => stmt.span,
+            Statement::ForStatement(stmt) => stmt.span,
+            Statement::FunctionDeclaration(stmt) => stmt.span,
+            Statement::IfStatement(stmt) => stmt.span,
+            Statement::LabeledStatement(stmt) => stmt.span,
+            Statement::ReturnStatement(stmt) => stmt.span,
+            Statement::SwitchStatement(stmt) => stmt.span,
+            Statement::ThrowStatement(stmt) => stmt.span,
+            Statement::TryStatement(stmt) => stmt.span,
+            Statement::VariableStatement(stmt) => stmt.span,
+            Statement::WhileStatement(stmt) => stmt.span,
+            Statement::WithStatement(stmt) => stmt.span,
+        }
+    }
 }
 
 /// A block statement.
 #[derive(Debug)]
 pub struct BlockStatement {
     pub stmts: Vec<Statement>,
+    pub span: Span,
+}
+
+/// A `break` statement. For example, `break a;`.
+#[derive(Debug)]
+pub struct BreakStatement {
+    pub label: Option<Identifier>,
+    pub span: Span,
+}
+
+/// A `catch` clause of a `try` statement. For example, `catch (e) {}`.
+#[derive(Debug)]
+pub struct CatchClause {
+    /// The binding of the thrown value, which is optional, as in `catch {}`.
+    pub param: Option<Pattern>,
+    pub body: BlockStatement,
+    pub span: Span,
+}
+
+/// A `continue` statement. For example, `continue a;`.
+#[derive(Debug)]
+pub struct ContinueStatement {
+    pub label: Option<Identifier>,
+    pub span: Span,
+}
+
+/// A `do`-`while` statement. For example, `do a(); while (b);`.
+#[derive(Debug)]
+pub struct DoWhileStatement {
+    pub body: Statement,
+    pub test: Expression,
+    pub span: Span,
+}
+
+/// An expression statement.
+#[derive(Debug)]
+pub struct ExpressionStatement {
+    pub expression: Expression,
+    pub span: Span,
+}
+
+/// A `for`-`in` statement. For example, `for (var a in b) {}`.
+#[derive(Debug)]
+pub struct ForInStatement {
+    pub left: ForInOfLeft,
+    pub right: Expression,
+    pub body: Statement,
+    pub span: Span,
+}
+
+/// The left-hand side of a `for`-`in` or `for`-`of` statement.
+#[derive(Debug)]
+pub enum ForInOfLeft {
+    /// An assignment target. For example, `a` in `for (a of b) {}`.
+    Pattern(Pattern),
+    /// A declaration. For example, `let a` in `for (let a of b) {}`.
+    VariableStatement(VariableStatement),
+}
+
+/// The initialization part of a `for` statement.
+#[derive(Debug)]
+pub enum ForInit {
+    Expression(Expression),
+    VariableStatement(VariableStatement),
+}
+
+/// A `for`-`of` statement. For example, `for (const a of b) {}` or
+/// `for await (const a of b) {}`.
+#[derive(Debug)]
+pub struct ForOfStatement {
+    pub left: ForInOfLeft,
+    pub right: Expression,
+    pub body: Statement,
+    pub is_await: bool,
+    pub span: Span,
+}
+
+/// A `for` statement. For example, `for (var i = 0; i < n; i++) {}`.
+#[derive(Debug)]
+pub struct ForStatement {
+    pub init: Option<ForInit>,
+    pub test: Option<Expression>,
+    pub update: Option<Expression>,
+    pub body: Statement,
+    pub span: Span,
+}
+
+/// An `if` statement. For example, `if (a) b; else c;`.
+#[derive(Debug)]
+pub struct IfStatement {
+    pub test: Expression,
+    pub consequent: Statement,
+    pub alternate: Option<Statement>,
+    pub span: Span,
+}
+
+/// A labeled statement. For example, `a: for (;;) {}`.
+#[derive(Debug)]
+pub struct LabeledStatement {
+    pub label: Identifier,
+    pub body: Statement,
+    pub span: Span,
+}
+
+/// A `return` statement. For example, `return a;`.
+#[derive(Debug)]
+pub struct ReturnStatement {
+    pub argument: Option<Expression>,
+    pub span: Span,
+}
+
+/// A `case` or `default` clause of a `switch` statement. For example,
+/// `case 1: a();`.
+#[derive(Debug)]
+pub struct SwitchCase {
+    /// The expression after `case`, which is `None` for a `default` clause.
+    pub test: Option<Expression>,
+    pub consequent: Vec<Statement>,
+    pub span: Span,
+}
+
+/// A `switch` statement. For example, `switch (a) { case 1: break; }`.
+#[derive(Debug)]
+pub struct SwitchStatement {
+    pub discriminant: Expression,
+    pub cases: Vec<SwitchCase>,
+    pub span: Span,
+}
+
+/// A `throw` statement. For example, `throw a;`.
+#[derive(Debug)]
+pub struct ThrowStatement {
+    pub argument: Expression,
+    pub span: Span,
+}
+
+/// A `try` statement, which has a `catch` clause, a `finally` block, or
+/// both. For example, `try {} catch (e) {} finally {}`.
+#[derive(Debug)]
+pub struct TryStatement {
+    pub block: BlockStatement,
+    pub handler: Option<CatchClause>,
+    pub finalizer: Option<BlockStatement>,
+    pub span: Span,
+}
+
+/// A `while` statement. For example, `while (a) b();`.
+#[derive(Debug)]
+pub struct WhileStatement {
+    pub test: Expression,
+    pub body: Statement,
+    pub span: Span,
+}
+
+/// A `with` statement, which is not allowed in strict mode code. For
+/// example, `with (a) b;`.
+#[derive(Debug)]
+pub struct WithStatement {
+    pub object: Expression,
+    pub body: Statement,
+    pub span: Span,
 }
 
 /// An import declaration.
@@ -96,23 +1072,189 @@ pub struct BlockStatement {
 pub struct ImportDeclaration {
     pub import_clause: Option<ImportClause>,
     pub module_specifier: ModuleSpecifier,
+    /// Import attributes, for example, `type: "json"` in
+    /// `import data from "./data.json" with { type: "json" }`.
+    pub attributes: Vec<ImportAttribute>,
+    /// Whether this is a TypeScript type-only import, `import type`.
+    pub type_only: bool,
+    pub span: Span,
 }
 
 /// A from clause.
 #[derive(Debug)]
 pub struct ModuleSpecifier {
+    /// The module name, with escape sequences decoded.
     pub value: String,
+    pub span: Span,
 }
 
 /// An import clause.
+///
+/// For example, in `import a, { b as c } from "m"`, the import clause is
+/// `a, { b as c }`.
+#[derive(Debug)]
+pub struct ImportClause {
+    /// The binding of the default export, for example, `a` in `import a from "m"`.
+    pub default_binding: Option<Identifier>,
+    /// The namespace import or named imports after the default binding.
+    pub named_bindings: Option<NamedBindings>,
+    pub span: Span,
+}
+
+/// The namespace import or named imports of an import clause.
+#[derive(Debug)]
+pub enum NamedBindings {
+    /// A namespace import. For example, `* as ns`.
+    NamespaceImport(Identifier),
+    /// Named imports. For example, `{ a, b as c }`.
+    NamedImports(Vec<ImportSpecifier>),
+}
+
+/// An import specifier.
+///
+/// For example, in `{ a as b }`, the imported name is `a` and the local name
+/// is `b`. Without `as`, the local name is the same as the imported name.
 #[derive(Debug)]
-pub enum ImportClause {
-    NamedImports(Vec<String>),
+pub struct ImportSpecifier {
+    pub imported: Identifier,
+    pub local: Identifier,
+    /// Whether this is a TypeScript type-only import specifier, `{ type a }`.
+    pub type_only: bool,
+    pub span: Span,
 }
 
-/// A variable statement.
+/// An import attribute. For example, `type: "json"`.
+#[derive(Debug)]
+pub struct ImportAttribute {
+    pub key: Identifier,
+    pub value: StringLiteral,
+    pub span: Span,
+}
+
+/// An export declaration.
+#[derive(Debug)]
+pub enum ExportDeclaration {
+    /// `export * from "m"` or `export * as ns from "m"`.
+    All(ExportAllDeclaration),
+    /// TypeScript `export = expression`.
+    Assignment(ExportAssignment),
+    /// An exported declaration. For example, `export const x = 1`.
+    Declaration(ExportedDeclaration),
+    /// `export default function f() {}` or `export default expression`.
+    Default(ExportDefaultDeclaration),
+    /// `export { a as b }` or `export { a as b } from "m"`.
+    Named(NamedExports),
+}
+
+impl ExportDeclaration {
+    /// Returns the source location of this export declaration.
+    pub fn span(&self) -> Span {
+        match self {
+            ExportDeclaration::All(decl) => decl.span,
+            ExportDeclaration::Assignment(decl) => decl.span,
+            ExportDeclaration::Declaration(decl) => decl.span,
+            ExportDeclaration::Default(decl) => decl.span,
+            ExportDeclaration::Named(decl) => decl.span,
+        }
+    }
+}
+
+/// An `export * from "m"` declaration.
+#[derive(Debug)]
+pub struct ExportAllDeclaration {
+    /// The name of the namespace object, for example, `ns` in
+    /// `export * as ns from "m"`.
+    pub exported: Option<Identifier>,
+    pub module_specifier: ModuleSpecifier,
+    /// Whether this is a TypeScript type-only export, `export type *`.
+    pub type_only: bool,
+    pub span: Span,
+}
+
+/// A TypeScript `export = expression` declaration.
+#[derive(Debug)]
+pub struct ExportAssignment {
+    pub expression: Expression,
+    pub span: Span,
+}
+
+/// An exported declaration.
+#[derive(Debug)]
+pub struct ExportedDeclaration {
+    pub declaration: Statement,
+    pub span: Span,
+}
+
+/// An `export default` declaration.
+#[derive(Debug)]
+pub struct ExportDefaultDeclaration {
+    pub declaration: DefaultDeclaration,
+    pub span: Span,
+}
+
+/// The declaration or expression that is exported by an `export default`
+/// declaration.
+#[derive(Debug)]
+pub enum DefaultDeclaration {
+    /// A class declaration, whose name is optional. For example,
+    /// `class {}` in `export default class {}`.
+    ClassDeclaration(Class),
+    /// An expression. For example, `a + b` in `export default a + b`.
+    Expression(Expression),
+    /// A function declaration, whose name is optional. For example,
+    /// `function () {}` in `export default function () {}`.
+    FunctionDeclaration(Function),
+}
+
+/// Named exports, optionally re-exported from another module.
+#[derive(Debug)]
+pub struct NamedExports {
+    pub specifiers: Vec<ExportSpecifier>,
+    /// The module to re-export from, for example, `"m"` in
+    /// `export { a } from "m"`.
+    pub module_specifier: Option<ModuleSpecifier>,
+    /// Whether this is a TypeScript type-only export, `export type { a }`.
+    pub type_only: bool,
+    pub span: Span,
+}
+
+/// An export specifier.
+///
+/// For example, in `{ a as b }`, the local name is `a` and the exported name
+/// is `b`. Without `as`, the exported name is the same as the local name.
+#[derive(Debug)]
+pub struct ExportSpecifier {
+    pub local: Identifier,
+    pub exported: Identifier,
+    /// Whether this is a TypeScript type-only export specifier, `{ type a }`.
+    pub type_only: bool,
+    pub span: Span,
+}
+
+/// A variable statement or a lexical declaration. For example,
+/// `let a = 1, [b, c] = d;`.
 #[derive(Debug)]
 pub struct VariableStatement {
-    pub binding_identifier: Identifier,
+    pub kind: VariableKind,
+    pub declarations: Vec<VariableDeclarator>,
+    pub span: Span,
+}
+
+/// The keyword of a variable statement or a lexical declaration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariableKind {
+    Const,
+    Let,
+    /// An explicit resource management declaration, `using a = b`.
+    Using,
+    Var,
+}
+
+/// A declarator of a variable statement or a lexical declaration. For
+/// example, `[b, c] = d` in `let a = 1, [b, c] = d;`.
+#[derive(Debug)]
+pub struct VariableDeclarator {
+    pub id: Pattern,
     pub initializer: Option<Expression>,
+    pub span: Span,
 }
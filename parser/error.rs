@@ -0,0 +1,510 @@
+// Copyright 2022 Pekka Enberg and contributors
+// SPDX-License-Identifier: MIT
+
+//! Parse errors
+
+use crate::options::EcmaVersion;
+use crate::regexp::RegExpError;
+use crate::span::Span;
+use crate::tokenizer::{Token, TokenError};
+use std::fmt;
+
+/// A parse error.
+#[derive(Debug)]
+pub struct ParseError {
+    /// What went wrong.
+    pub kind: ParseErrorKind,
+    /// Source location of the erroneous code.
+    pub span: Span,
+}
+
+impl ParseError {
+    pub fn new(kind: ParseErrorKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+
+    /// Returns the stable diagnostic code of this error.
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The kind of a parse error.
+#[derive(Debug, PartialEq)]
+pub enum ParseErrorKind {
+    /// An `await` expression in a module, outside of async functions and
+    /// the top level, for example, in a function that is not async.
+    AwaitOutsideAsync,
+    /// Two or more numeric separators in a row, for example, `1__0`.
+    ConsecutiveNumericSeparators,
+    /// A declaration or a statement was expected.
+    DeclarationOrStatementExpected,
+    /// A `delete` expression whose operand is an identifier, in strict mode
+    /// code.
+    DeleteIdentifierInStrictMode,
+    /// A numeric literal is missing digits, for example, `0x` or `1e`.
+    DigitExpected,
+    /// A class with more than one constructor.
+    DuplicateConstructor,
+    /// A `switch` statement with more than one `default` clause.
+    DuplicateDefaultClause,
+    /// A label that is already the label of an enclosing statement, for
+    /// example, `a: a: b;`.
+    DuplicateLabel(String),
+    /// A name bound by more than one parameter where that is not allowed,
+    /// for example, `(a, a) => a`.
+    DuplicateParameter(String),
+    /// An object literal with more than one `__proto__` property, for
+    /// example, `{ __proto__: a, "__proto__": b }`.
+    DuplicateProto,
+    /// `eval` or `arguments` as a binding name in strict mode code, for
+    /// example, `"use strict"; var eval;`.
+    EvalOrArgumentsInStrictMode(String),
+    /// An expression was expected.
+    ExpressionExpected,
+    /// Syntax that is not available in the targeted ECMAScript version.
+    FeatureNotAvailable {
+        feature: &'static str,
+        required: EcmaVersion,
+    },
+    /// A variable declaration with an initializer in the head of a
+    /// `for`-`of` statement, or of a `for`-`in` statement other than a legacy
+    /// `var` declaration in sloppy mode code.
+    ForInOfInitializer,
+    /// A variable declaration with more than one declarator in the head of
+    /// a `for`-`in` or `for`-`of` statement.
+    ForInOfMultipleDeclarations,
+    /// A getter with parameters, for example, `get a(b) {}`.
+    GetterParameters,
+    /// An identifier start immediately follows a numeric literal, for
+    /// example, `3in`.
+    IdentifierAfterNumericLiteral,
+    /// An identifier was expected.
+    IdentifierExpected,
+    /// `import.meta` outside of a module.
+    ImportMetaOutsideModule,
+    /// The target of an assignment or an update expression that cannot be
+    /// assigned to, for example, `a() = 1` or `a?.b++`.
+    InvalidAssignmentTarget,
+    /// A `break` statement without a label outside of loops and `switch`
+    /// statements.
+    InvalidBreak,
+    /// A character that cannot start any token.
+    InvalidCharacter(char),
+    /// A class constructor that is a getter, a setter, a generator or an
+    /// async method, or a field named `constructor`.
+    InvalidConstructor,
+    /// A `continue` statement outside of loops, or with the label of a
+    /// statement that is not a loop.
+    InvalidContinue,
+    /// A malformed escape sequence in a string literal.
+    InvalidEscapeSequence,
+    /// A regular expression literal with an invalid pattern or flags.
+    InvalidRegularExpression(RegExpError),
+    /// JSX syntax is enabled in the options, but it is not supported yet.
+    JsxNotSupported,
+    /// A decimal literal with a leading zero, such as `08`, in strict mode
+    /// code.
+    LeadingZeroInStrictMode,
+    /// A line terminator where it is not allowed, for example, after
+    /// `throw`.
+    LineBreakNotPermitted,
+    /// The `as` keyword of a namespace import is missing.
+    MissingAs,
+    /// The `from` keyword of an import declaration is missing.
+    MissingFrom,
+    /// `??` mixed with `||` or `&&` without parentheses, for example,
+    /// `a ?? b || c`.
+    MixedNullishCoalescing,
+    /// A `const` or `using` declaration, or a destructuring declaration,
+    /// without an initializer, for example, `const a;`.
+    MissingInitializer,
+    /// An import or export declaration appears outside of a module.
+    ModuleDeclarationInScript,
+    /// An import or export declaration in a module, but not at its top
+    /// level, for example, inside a block.
+    ModuleDeclarationNotAtTopLevel,
+    /// `new.target` outside of functions and class elements.
+    NewTargetOutsideFunction,
+    /// A numeric separator that is not between two digits, for example,
+    /// `1_`.
+    NumericSeparatorNotAllowed,
+    /// A legacy octal escape sequence, or a `\8` or `\9` escape sequence,
+    /// in strict mode code.
+    OctalEscapeInStrictMode,
+    /// A legacy octal literal, such as `017`, in strict mode code.
+    OctalLiteralInStrictMode,
+    /// A class element named `#constructor`.
+    PrivateConstructor,
+    /// A private name outside of a class body, for example, `a.#b`.
+    PrivateIdentifierOutsideClass,
+    /// A word used as an identifier where it is reserved, for example,
+    /// `yield` in a generator, `static` in strict mode code, or a reserved
+    /// word written with escape sequences, such as `\u0069f`.
+    ReservedWordAsIdentifier(String),
+    /// A rest element with an initializer, for example, `[...a = 1] = b`.
+    RestElementInitializer,
+    /// A rest element or rest parameter that is followed by another element
+    /// or by a trailing comma, for example, `(...a, b) => a`.
+    RestElementNotLast,
+    /// A `return` statement outside of functions.
+    ReturnOutsideFunction,
+    /// A shorthand property with an initializer, such as `a = 1` in
+    /// `{ a = 1 }`, in an object literal that is not a destructuring
+    /// pattern.
+    ShorthandPropertyInitializer,
+    /// A setter without exactly one parameter, or with a rest parameter,
+    /// for example, `set a() {}`.
+    SetterParameters,
+    /// A static class method or field named `prototype`.
+    StaticPrototype,
+    /// A `super` call outside of the constructor of a class with an
+    /// `extends` clause.
+    SuperCallOutsideConstructor,
+    /// A `super` property access outside of methods and class elements.
+    SuperPropertyOutsideMethod,
+    /// A tagged template in an optional chain, for example, `a?.b`c``.
+    TaggedTemplateInOptionalChain,
+    /// TypeScript syntax when TypeScript is disabled.
+    TypeScriptOnly(&'static str),
+    /// An unparenthesized unary expression as the left operand of `**`, for
+    /// example, `-a ** b`.
+    UnaryBeforeExponentiation,
+    /// A `break` or `continue` statement with a label that is not the label
+    /// of an enclosing statement.
+    UndefinedLabel(String),
+    /// A token other than the expected ones was found. A `found` token of
+    /// `None` means the end of input. An empty `expected` set means that the
+    /// token is not allowed here, but there is no single token that would be.
+    UnexpectedToken {
+        found: Option<Token>,
+        expected: Vec<Token>,
+    },
+    /// A multi-line comment is missing its closing `*/`.
+    UnterminatedComment,
+    /// A regular expression literal is missing its closing `/`.
+    UnterminatedRegularExpression,
+    /// A string literal is missing its closing quote.
+    UnterminatedString,
+    /// A template is missing its closing backtick.
+    UnterminatedTemplate,
+    /// A `"use strict"` directive in a function whose parameter list is not
+    /// simple, for example, `function f(a = 1) { "use strict"; }`.
+    UseStrictWithNonSimpleParameters,
+    /// A `with` statement in strict mode code.
+    WithInStrictMode,
+}
+
+impl ParseErrorKind {
+    /// Returns the stable diagnostic code of this kind of error.
+    ///
+    /// The codes are part of the public interface: a code is never reused
+    /// for a different kind of error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseErrorKind::DeclarationOrStatementExpected => "E0001",
+            ParseErrorKind::ExpressionExpected => "E0002",
+            ParseErrorKind::IdentifierExpected => "E0003",
+            ParseErrorKind::MissingFrom => "E0004",
+            ParseErrorKind::UnexpectedToken { .. } => "E0005",
+            ParseErrorKind::UnterminatedString => "E0006",
+            ParseErrorKind::InvalidCharacter(_) => "E0007",
+            ParseErrorKind::ModuleDeclarationInScript => "E0008",
+            ParseErrorKind::MissingAs => "E0009",
+            ParseErrorKind::InvalidEscapeSequence => "E0010",
+            ParseErrorKind::ConsecutiveNumericSeparators => "E0011",
+            ParseErrorKind::NumericSeparatorNotAllowed => "E0012",
+            ParseErrorKind::DigitExpected => "E0013",
+            ParseErrorKind::IdentifierAfterNumericLiteral => "E0014",
+            ParseErrorKind::UnterminatedTemplate => "E0015",
+            ParseErrorKind::UnterminatedComment => "E0016",
+            ParseErrorKind::FeatureNotAvailable { .. } => "E0017",
+            ParseErrorKind::OctalLiteralInStrictMode => "E0018",
+            ParseErrorKind::LeadingZeroInStrictMode => "E0019",
+            ParseErrorKind::OctalEscapeInStrictMode => "E0020",
+            ParseErrorKind::TypeScriptOnly(_) => "E0021",
+            ParseErrorKind::UnterminatedRegularExpression => "E0022",
+            ParseErrorKind::InvalidRegularExpression(_) => "E0023",
+            ParseErrorKind::RestElementNotLast => "E0024",
+            ParseErrorKind::DuplicateConstructor => "E0025",
+            ParseErrorKind::InvalidConstructor => "E0026",
+            ParseErrorKind::ReturnOutsideFunction => "E0027",
+            ParseErrorKind::InvalidBreak => "E0028",
+            ParseErrorKind::InvalidContinue => "E0029",
+            ParseErrorKind::UndefinedLabel(_) => "E0030",
+            ParseErrorKind::DuplicateLabel(_) => "E0031",
+            ParseErrorKind::DuplicateDefaultClause => "E0032",
+            ParseErrorKind::ForInOfInitializer => "E0033",
+            ParseErrorKind::LineBreakNotPermitted => "E0034",
+            ParseErrorKind::WithInStrictMode => "E0035",
+            ParseErrorKind::MissingInitializer => "E0036",
+            ParseErrorKind::ForInOfMultipleDeclarations => "E0037",
+            ParseErrorKind::DuplicateProto => "E0038",
+            ParseErrorKind::ShorthandPropertyInitializer => "E0039",
+            ParseErrorKind::InvalidAssignmentTarget => "E0040",
+            ParseErrorKind::DeleteIdentifierInStrictMode => "E0041",
+            ParseErrorKind::PrivateIdentifierOutsideClass => "E0042",
+            ParseErrorKind::TaggedTemplateInOptionalChain => "E0043",
+            ParseErrorKind::ImportMetaOutsideModule => "E0044",
+            ParseErrorKind::UnaryBeforeExponentiation => "E0045",
+            ParseErrorKind::MixedNullishCoalescing => "E0046",
+            ParseErrorKind::RestElementInitializer => "E0047",
+            ParseErrorKind::AwaitOutsideAsync => "E0048",
+            ParseErrorKind::ReservedWordAsIdentifier(_) => "E0049",
+            ParseErrorKind::ModuleDeclarationNotAtTopLevel => "E0050",
+            ParseErrorKind::PrivateConstructor => "E0051",
+            ParseErrorKind::StaticPrototype => "E0052",
+            ParseErrorKind::GetterParameters => "E0053",
+            ParseErrorKind::SetterParameters => "E0054",
+            ParseErrorKind::JsxNotSupported => "E0055",
+            ParseErrorKind::DuplicateParameter(_) => "E0056",
+            ParseErrorKind::UseStrictWithNonSimpleParameters => "E0057",
+            ParseErrorKind::EvalOrArgumentsInStrictMode(_) => "E0058",
+            ParseErrorKind::NewTargetOutsideFunction => "E0059",
+            ParseErrorKind::SuperPropertyOutsideMethod => "E0060",
+            ParseErrorKind::SuperCallOutsideConstructor => "E0061",
+        }
+    }
+}
+
+impl From<TokenError> for ParseErrorKind {
+    fn from(error: TokenError) -> Self {
+        match error {
+            TokenError::ConsecutiveNumericSeparators => {
+                ParseErrorKind::ConsecutiveNumericSeparators
+            }
+            TokenError::DigitExpected => ParseErrorKind::DigitExpected,
+            TokenError::IdentifierAfterNumericLiteral => {
+                ParseErrorKind::IdentifierAfterNumericLiteral
+            }
+            TokenError::InvalidCharacter(ch) => ParseErrorKind::InvalidCharacter(ch),
+            TokenError::InvalidEscapeSequence => ParseErrorKind::InvalidEscapeSequence,
+            TokenError::NumericSeparatorNotAllowed => ParseErrorKind::NumericSeparatorNotAllowed,
+            TokenError::UnterminatedComment => ParseErrorKind::UnterminatedComment,
+            TokenError::UnterminatedRegularExpressionLiteral => {
+                ParseErrorKind::UnterminatedRegularExpression
+            }
+            TokenError::UnterminatedStringLiteral => ParseErrorKind::UnterminatedString,
+            TokenError::UnterminatedTemplateLiteral => ParseErrorKind::UnterminatedTemplate,
+        }
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::AwaitOutsideAsync => write!(
+                f,
+                "`await` is only allowed in async functions and at the top level of modules."
+            ),
+            ParseErrorKind::ConsecutiveNumericSeparators => {
+                write!(
+                    f,
+                    "Multiple consecutive numeric separators are not permitted."
+                )
+            }
+            ParseErrorKind::DeclarationOrStatementExpected => {
+                write!(f, "Declaration or statement expected.")
+            }
+            ParseErrorKind::DeleteIdentifierInStrictMode => write!(
+                f,
+                "`delete` cannot be applied to an identifier in strict mode."
+            ),
+            ParseErrorKind::DigitExpected => write!(f, "Digit expected."),
+            ParseErrorKind::DuplicateConstructor => {
+                write!(f, "Multiple constructor implementations are not allowed.")
+            }
+            ParseErrorKind::DuplicateDefaultClause => write!(
+                f,
+                "A `default` clause cannot appear more than once in a `switch` statement."
+            ),
+            ParseErrorKind::DuplicateLabel(label) => write!(f, "Duplicate label `{}`.", label),
+            ParseErrorKind::DuplicateParameter(name) => {
+                write!(f, "Duplicate parameter name `{}`.", name)
+            }
+            ParseErrorKind::DuplicateProto => write!(
+                f,
+                "Duplicate `__proto__` properties are not allowed in object literals."
+            ),
+            ParseErrorKind::EvalOrArgumentsInStrictMode(name) => write!(
+                f,
+                "`{}` cannot be used as a binding name in strict mode.",
+                name
+            ),
+            ParseErrorKind::ExpressionExpected => write!(f, "Expression expected."),
+            ParseErrorKind::FeatureNotAvailable { feature, required } => write!(
+                f,
+                "{} are not available when targeting lower than {}.",
+                feature, required
+            ),
+            ParseErrorKind::ForInOfInitializer => write!(
+                f,
+                "The variable declaration of a `for`-`in` or `for`-`of` statement cannot have an initializer."
+            ),
+            ParseErrorKind::ForInOfMultipleDeclarations => write!(
+                f,
+                "Only a single variable declaration is allowed in a `for`-`in` or `for`-`of` statement."
+            ),
+            ParseErrorKind::GetterParameters => write!(f, "A `get` accessor cannot have parameters."),
+            ParseErrorKind::IdentifierAfterNumericLiteral => write!(
+                f,
+                "An identifier or keyword cannot immediately follow a numeric literal."
+            ),
+            ParseErrorKind::IdentifierExpected => write!(f, "Identifier expected"),
+            ParseErrorKind::ImportMetaOutsideModule => {
+                write!(f, "`import.meta` may only appear in a module.")
+            }
+            ParseErrorKind::InvalidAssignmentTarget => {
+                write!(f, "This expression cannot be assigned to.")
+            }
+            ParseErrorKind::InvalidBreak => write!(
+                f,
+                "A `break` statement can only be used within an enclosing iteration or `switch` statement."
+            ),
+            ParseErrorKind::InvalidCharacter(ch) => write!(f, "Invalid character `{}`.", ch),
+            ParseErrorKind::InvalidConstructor => write!(
+                f,
+                "A class constructor cannot be a field, an accessor, a generator or async."
+            ),
+            ParseErrorKind::InvalidContinue => write!(
+                f,
+                "A `continue` statement can only jump to an enclosing iteration statement."
+            ),
+            ParseErrorKind::InvalidEscapeSequence => write!(f, "Invalid escape sequence."),
+            ParseErrorKind::InvalidRegularExpression(error) => {
+                write!(f, "Invalid regular expression: {}.", error)
+            }
+            ParseErrorKind::JsxNotSupported => write!(f, "JSX is not supported yet."),
+            ParseErrorKind::LeadingZeroInStrictMode => write!(
+                f,
+                "Decimals with leading zeros are not allowed in strict mode."
+            ),
+            ParseErrorKind::LineBreakNotPermitted => write!(f, "Line break not permitted here."),
+            ParseErrorKind::MissingAs => write!(f, "`as` expected"),
+            ParseErrorKind::MissingFrom => write!(f, "`from` expected"),
+            ParseErrorKind::MissingInitializer => write!(
+                f,
+                "`const`, `using` and destructuring declarations must be initialized."
+            ),
+            ParseErrorKind::MixedNullishCoalescing => write!(
+                f,
+                "`??` cannot be mixed with `||` or `&&` without parentheses."
+            ),
+            ParseErrorKind::ModuleDeclarationInScript => write!(
+                f,
+                "`import` and `export` declarations may only appear in a module."
+            ),
+            ParseErrorKind::ModuleDeclarationNotAtTopLevel => write!(
+                f,
+                "`import` and `export` declarations may only appear at the top level of a module."
+            ),
+            ParseErrorKind::NewTargetOutsideFunction => write!(
+                f,
+                "`new.target` may only appear in functions and class elements."
+            ),
+            ParseErrorKind::NumericSeparatorNotAllowed => {
+                write!(f, "Numeric separators are not allowed here.")
+            }
+            ParseErrorKind::OctalEscapeInStrictMode => {
+                write!(f, "Octal escape sequences are not allowed in strict mode.")
+            }
+            ParseErrorKind::OctalLiteralInStrictMode => {
+                write!(f, "Octal literals are not allowed in strict mode.")
+            }
+            ParseErrorKind::PrivateConstructor => {
+                write!(f, "A class member cannot be named `#constructor`.")
+            }
+            ParseErrorKind::PrivateIdentifierOutsideClass => write!(
+                f,
+                "Private identifiers are not allowed outside class bodies."
+            ),
+            ParseErrorKind::ReservedWordAsIdentifier(word) => write!(
+                f,
+                "`{}` is reserved and cannot be used as an identifier here.",
+                word
+            ),
+            ParseErrorKind::RestElementInitializer => {
+                write!(f, "A rest element cannot have an initializer.")
+            }
+            ParseErrorKind::RestElementNotLast => write!(
+                f,
+                "A rest element must be last in a parameter list or destructuring pattern."
+            ),
+            ParseErrorKind::ReturnOutsideFunction => write!(
+                f,
+                "A `return` statement can only be used within a function body."
+            ),
+            ParseErrorKind::ShorthandPropertyInitializer => write!(
+                f,
+                "A shorthand property can only have an initializer in a destructuring pattern."
+            ),
+            ParseErrorKind::SetterParameters => write!(
+                f,
+                "A `set` accessor must have exactly one parameter, which cannot be a rest parameter."
+            ),
+            ParseErrorKind::StaticPrototype => {
+                write!(f, "A static class member cannot be named `prototype`.")
+            }
+            ParseErrorKind::SuperCallOutsideConstructor => write!(
+                f,
+                "`super` calls may only appear in constructors of classes with an `extends` clause."
+            ),
+            ParseErrorKind::SuperPropertyOutsideMethod => write!(
+                f,
+                "`super` properties may only be accessed in methods and class elements."
+            ),
+            ParseErrorKind::TaggedTemplateInOptionalChain => {
+                write!(f, "Tagged templates cannot be used in optional chains.")
+            }
+            ParseErrorKind::TypeScriptOnly(syntax) => {
+                write!(f, "{} can only be used in TypeScript files.", syntax)
+            }
+            ParseErrorKind::UnaryBeforeExponentiation => write!(
+                f,
+                "The left operand of `**` cannot be an unparenthesized unary expression."
+            ),
+            ParseErrorKind::UndefinedLabel(label) => write!(f, "Undefined label `{}`.", label),
+            ParseErrorKind::UnexpectedToken { found, expected } => {
+                let found = match found {
+                    Some(found) => format!("`{:?}`", found),
+                    None => "end of input".to_string(),
+                };
+                match expected.as_slice() {
+                    [] => write!(f, "Unexpected token {}", found),
+                    [expected] => write!(f, "Expected token `{:?}`, but was {}", expected, found),
+                    expected => {
+                        let expected: Vec<String> =
+                            expected.iter().map(|t| format!("`{:?}`", t)).collect();
+                        write!(
+                            f,
+                            "Expected one of {}, but was {}",
+                            expected.join(", "),
+                            found
+                        )
+                    }
+                }
+            }
+            ParseErrorKind::UnterminatedComment => write!(f, "`*/` expected."),
+            ParseErrorKind::UnterminatedRegularExpression => {
+                write!(f, "Unterminated regular expression literal.")
+            }
+            ParseErrorKind::UnterminatedString => write!(f, "Unterminated string literal."),
+            ParseErrorKind::UnterminatedTemplate => write!(f, "Unterminated template literal."),
+            ParseErrorKind::UseStrictWithNonSimpleParameters => write!(
+                f,
+                "A `\"use strict\"` directive is not allowed in a function with a parameter list that is not simple."
+            ),
+            ParseErrorKind::WithInStrictMode => {
+                write!(f, "`with` statements are not allowed in strict mode.")
+            }
+        }
+    }
+}
@@ -2,5 +2,9 @@
 // SPDX-License-Identifier: MIT
 
 pub mod ast;
+pub mod error;
+pub mod options;
 pub mod parser;
+pub mod regexp;
+pub mod span;
 pub mod tokenizer;
@@ -0,0 +1,178 @@
+// Copyright 2022 Pekka Enberg and contributors
+// SPDX-License-Identifier: MIT
+
+//! Parser options
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Options that control how the parser parses source text.
+///
+/// The options are built by starting from the defaults and overriding them:
+///
+/// ```
+/// use tsparser_parser::options::{ParserOptions, SourceType};
+///
+/// let options = ParserOptions::new()
+///     .source_type(SourceType::Module)
+///     .preserve_comments(true);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ParserOptions {
+    pub(crate) preserve_comments: bool,
+    pub(crate) source_type: SourceType,
+    pub(crate) ecma_version: EcmaVersion,
+    pub(crate) typescript: bool,
+    pub(crate) jsx: bool,
+    pub(crate) strict: bool,
+    pub(crate) allow_return_outside_function: bool,
+    pub(crate) recover: bool,
+}
+
+impl ParserOptions {
+    /// Returns the default options, which parse TypeScript scripts that
+    /// target the latest ECMAScript version.
+    pub fn new() -> Self {
+        Self {
+            preserve_comments: false,
+            source_type: SourceType::Script,
+            ecma_version: EcmaVersion::default(),
+            typescript: true,
+            jsx: false,
+            strict: false,
+            allow_return_outside_function: false,
+            recover: false,
+        }
+    }
+
+    /// Sets whether comments are recorded in the comment table of the
+    /// parsed script or module and attached to the nodes next to them.
+    pub fn preserve_comments(mut self, preserve_comments: bool) -> Self {
+        self.preserve_comments = preserve_comments;
+        self
+    }
+
+    /// Sets whether `Parser::parse` parses the source text as a script or
+    /// as a module.
+    pub fn source_type(mut self, source_type: SourceType) -> Self {
+        self.source_type = source_type;
+        self
+    }
+
+    /// Sets the ECMAScript version to target. Syntax from later versions is
+    /// reported as an error.
+    pub fn ecma_version(mut self, ecma_version: EcmaVersion) -> Self {
+        self.ecma_version = ecma_version;
+        self
+    }
+
+    /// Sets whether TypeScript syntax is allowed.
+    pub fn typescript(mut self, typescript: bool) -> Self {
+        self.typescript = typescript;
+        self
+    }
+
+    /// Sets whether JSX syntax is allowed.
+    ///
+    /// JSX elements are not parsed yet, so `Parser::parse` reports an error
+    /// if this is set.
+    pub fn jsx(mut self, jsx: bool) -> Self {
+        self.jsx = jsx;
+        self
+    }
+
+    /// Sets whether scripts are parsed as strict mode code. Modules are
+    /// always strict mode code.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets whether `return` statements are allowed outside of functions.
+    pub fn allow_return_outside_function(mut self, allow: bool) -> Self {
+        self.allow_return_outside_function = allow;
+        self
+    }
+
+    /// Sets whether `Parser::parse` recovers from syntax errors instead of
+    /// stopping at the first one.
+    pub fn recover(mut self, recover: bool) -> Self {
+        self.recover = recover;
+        self
+    }
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The goal symbol that the source text is parsed with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SourceType {
+    #[default]
+    Script,
+    Module,
+}
+
+/// An ECMAScript version.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EcmaVersion {
+    Es5,
+    Es2015,
+    Es2016,
+    Es2017,
+    Es2018,
+    Es2019,
+    Es2020,
+    Es2021,
+    Es2022,
+    Es2023,
+    Es2024,
+    #[default]
+    Es2025,
+}
+
+impl EcmaVersion {
+    const ALL: [EcmaVersion; 12] = [
+        EcmaVersion::Es5,
+        EcmaVersion::Es2015,
+        EcmaVersion::Es2016,
+        EcmaVersion::Es2017,
+        EcmaVersion::Es2018,
+        EcmaVersion::Es2019,
+        EcmaVersion::Es2020,
+        EcmaVersion::Es2021,
+        EcmaVersion::Es2022,
+        EcmaVersion::Es2023,
+        EcmaVersion::Es2024,
+        EcmaVersion::Es2025,
+    ];
+}
+
+impl fmt::Display for EcmaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EcmaVersion::Es5 => write!(f, "ES5"),
+            version => write!(f, "ES{}", 2015 + (*version as usize) - 1),
+        }
+    }
+}
+
+impl FromStr for EcmaVersion {
+    type Err = String;
+
+    /// Parses a version such as `es2020`, `ES2020`, `2020`, `es5` or `latest`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_ascii_lowercase();
+        if s == "latest" {
+            return Ok(EcmaVersion::default());
+        }
+        let name = s.strip_prefix("es").unwrap_or(&s);
+        EcmaVersion::ALL
+            .into_iter()
+            .find(|version| version.to_string()[2..] == *name)
+            .ok_or_else(|| format!("unknown ECMAScript version `{}`", s))
+    }
+}
@@ -4,216 +4,4451 @@
 //! ECMAScript Parser
 
 use crate::ast::{
-    BlockStatement, Expression, Identifier, ImportClause, ImportDeclaration, ModuleSpecifier,
-    Script, Statement, VariableStatement,
+    ArrayExpression, ArrayPattern, ArrowFunctionBody, ArrowFunctionExpression,
+    AssignmentExpression, AssignmentOp, AssignmentPattern, AwaitExpression, BigIntLiteral,
+    BinaryExpression, BinaryOp, BlockStatement, BooleanLiteral, BreakStatement, CallExpression,
+    CatchClause, ChainExpression, Class, ClassElement, Comment, CommentAttachment, CommentKind,
+    ConditionalExpression, ContinueStatement, DefaultDeclaration, DoWhileStatement,
+    ExportAllDeclaration, ExportAssignment, ExportDeclaration, ExportDefaultDeclaration,
+    ExportSpecifier, ExportedDeclaration, Expression, ExpressionOrSpread, ExpressionStatement,
+    ForInOfLeft, ForInStatement, ForInit, ForOfStatement, ForStatement, Function, Identifier,
+    IfStatement, ImportAttribute, ImportClause, ImportDeclaration, ImportExpression,
+    ImportSpecifier, LabeledStatement, MemberExpression, MemberProperty, MetaProperty,
+    MethodDefinition, MethodKind, Module, ModuleItem, ModuleSpecifier, NamedBindings, NamedExports,
+    NewExpression, NumericLiteral, ObjectExpression, ObjectPattern, ObjectPatternProperty,
+    ObjectProperty, ParenthesizedExpression, Pattern, PatternProperty, Program, Property,
+    PropertyDefinition, PropertyKey, RegExpLiteral, RestElement, ReturnStatement, Script,
+    SequenceExpression, SpreadElement, Statement, StringLiteral, SwitchCase, SwitchStatement,
+    TaggedTemplateExpression, TemplateElement, TemplateLiteral, ThrowStatement, TryStatement,
+    UnaryExpression, UnaryOp, UpdateExpression, UpdateOp, VariableDeclarator, VariableKind,
+    VariableStatement, WhileStatement, WithStatement, YieldExpression,
 };
-use crate::tokenizer::{Token, Tokenizer};
+pub use crate::error::{ParseError, ParseErrorKind};
+pub use crate::options::{EcmaVersion, ParserOptions, SourceType};
+use crate::regexp;
+use crate::span::{Position, Span};
+use crate::tokenizer::{ContextualKeyword, Token, Tokenizer};
 use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::io::BufRead;
+use std::ops::Range;
 
-#[derive(Debug)]
-pub struct ParseError {
-    pub reason: String,
+/// A token that was read ahead from the tokenizer.
+///
+/// The source text and location of the token are stored alongside it,
+/// because the tokenizer has already moved on by the time the token is
+/// consumed.
+#[derive(Clone)]
+struct Lookahead {
+    token: Token,
+    slice: String,
+    value: String,
+    invalid_escape: bool,
+    keyword: Option<ContextualKeyword>,
+    /// Whether there is a line terminator before the token.
+    line_terminator_before: bool,
+    span: Span,
+}
+
+/// The statements that enclose the statement being parsed, up to the
+/// innermost function or class static block.
+#[derive(Default)]
+struct StatementContext {
+    /// Whether `return` statements are allowed.
+    in_function: bool,
+    /// Whether the statement is inside a loop.
+    in_iteration: bool,
+    /// Whether the statement is inside a `switch` statement.
+    in_switch: bool,
+    /// Whether `await` is an operator, which it is in async functions.
+    in_async: bool,
+    /// Whether `yield` is an operator, which it is in generators.
+    in_generator: bool,
+    /// Whether the statement is outside of all functions and class static
+    /// blocks, where `await` is an operator in modules.
+    top_level: bool,
+    /// Whether the statement is in the body of a function whose parameter
+    /// list is not simple, where a `"use strict"` directive is not allowed.
+    non_simple_parameters: bool,
+    /// Whether `new.target` is allowed, which it is in non-arrow functions
+    /// and class elements, and in arrow functions nested in them.
+    allow_new_target: bool,
+    /// Whether `super` property accesses are allowed, which they are in
+    /// methods and class elements, and in arrow functions nested in them.
+    allow_super_property: bool,
+    /// Whether `super` calls are allowed, which they are in constructors of
+    /// classes with an `extends` clause, and in arrow functions nested in
+    /// them.
+    allow_super_call: bool,
+    /// Labels of the enclosing labeled statements, from outermost to
+    /// innermost.
+    labels: Vec<Label>,
+}
+
+/// The label of a labeled statement.
+struct Label {
+    name: String,
+    /// Whether the labeled statement is a loop, which makes the label a
+    /// valid target of `continue` statements.
+    iteration: bool,
+}
+
+/// An element of a parenthesized list, which is either a parenthesized
+/// expression, the parameter list of an arrow function, or the arguments of
+/// a call of a function named `async`.
+enum CoverElement {
+    Expression(Expression),
+    /// An element that is only valid as a parameter, such as `...a` or
+    /// `a = 1`.
+    Parameter(Pattern),
+    /// A spread element in the arguments of `async(...a)`, which is a rest
+    /// parameter if the list turns out to be the parameters of an async
+    /// arrow function.
+    SpreadElement(SpreadElement),
 }
 
 pub struct Parser<'a, R: BufRead> {
-    preserve_comments: bool,
+    options: ParserOptions,
+    /// The comment table, in source order.
+    comments: Vec<Comment>,
+    /// Index of the first comment in `comments` that may still be attached
+    /// as a leading comment.
+    unattached_comments: usize,
     tokenizer: Tokenizer<'a, R>,
-    lookahead: RefCell<Option<Token>>,
+    /// Tokens that have been read ahead, which is at most two tokens.
+    lookahead: RefCell<VecDeque<Lookahead>>,
+    /// Source text of the last consumed token.
+    slice: String,
+    /// Cooked value of the last consumed token.
+    value: String,
+    /// Whether the last consumed token is a template with an invalid escape
+    /// sequence.
+    invalid_escape: bool,
+    /// Contextual keyword that the last consumed token spells, if it is an
+    /// identifier name.
+    keyword: Option<ContextualKeyword>,
+    /// The last consumed token.
+    token: Option<Token>,
+    /// Whether there is a line terminator before the last consumed token.
+    line_terminator_before: bool,
+    /// Source location of the last consumed token.
+    span: Span,
+    /// Source location of the token before the last consumed one.
+    previous_span: Span,
+    /// If set, the source text is parsed with the Module goal symbol;
+    /// otherwise, with the Script goal symbol.
+    module: bool,
+    /// If set, syntax errors are recorded in `errors` and parsing continues.
+    recover: bool,
+    /// If set, the parser is inside a class, which is always strict mode
+    /// code.
+    in_class: bool,
+    /// If set, a `"use strict"` directive applies to the code being parsed.
+    use_strict: bool,
+    /// Whether the next statement is in a directive prologue, which is the
+    /// sequence of string literal expression statements at the start of a
+    /// script or a function body.
+    directive_prologue: bool,
+    /// The statements that enclose the statement being parsed.
+    context: StatementContext,
+    /// Whether `in` is a binary operator, which it is not in the
+    /// initialization part of a `for` statement.
+    allow_in: bool,
+    /// The first error in the expression being parsed that applies only if
+    /// the expression is not reinterpreted as a pattern, such as the
+    /// initializer in `{ a = 1 }`.
+    pattern_error: Option<ParseError>,
+    /// Spans of the spread elements of array and object literals that are
+    /// followed by a trailing comma, which is not allowed after the rest
+    /// element if the literal is reinterpreted as a pattern.
+    spreads_before_trailing_comma: Vec<Span>,
+    errors: Vec<ParseError>,
 }
 
 impl<'a, R: BufRead> Parser<'a, R> {
     pub fn new(tokenizer: Tokenizer<'a, R>) -> Self {
+        Self::with_options(tokenizer, ParserOptions::default())
+    }
+
+    pub fn with_options(tokenizer: Tokenizer<'a, R>, options: ParserOptions) -> Self {
+        let context = StatementContext {
+            in_function: options.allow_return_outside_function,
+            top_level: true,
+            ..StatementContext::default()
+        };
         Self {
-            preserve_comments: false,
+            options,
+            comments: Vec::new(),
+            unattached_comments: 0,
             tokenizer,
-            lookahead: RefCell::new(None),
+            lookahead: RefCell::new(VecDeque::new()),
+            slice: String::new(),
+            value: String::new(),
+            invalid_escape: false,
+            keyword: None,
+            token: None,
+            line_terminator_before: false,
+            span: Span::default(),
+            previous_span: Span::default(),
+            module: false,
+            recover: false,
+            in_class: false,
+            use_strict: false,
+            directive_prologue: false,
+            context,
+            allow_in: true,
+            pattern_error: None,
+            spreads_before_trailing_comma: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Parse the source text as a script or a module, as set by the
+    /// options.
+    ///
+    /// If error recovery is enabled in the options, all syntax errors are
+    /// returned alongside the AST. Otherwise, parsing stops at the first
+    /// syntax error and no AST is returned.
+    pub fn parse(&mut self) -> (Option<Program>, Vec<ParseError>) {
+        fn fail_fast<T>(result: Result<T, ParseError>) -> (Option<T>, Vec<ParseError>) {
+            match result {
+                Ok(ast) => (Some(ast), Vec::new()),
+                Err(error) => (None, vec![error]),
+            }
+        }
+        if self.options.jsx {
+            let error = ParseError::new(ParseErrorKind::JsxNotSupported, Span::default());
+            return (None, vec![error]);
+        }
+        match (self.options.source_type, self.options.recover) {
+            (SourceType::Script, true) => {
+                let (script, errors) = self.parse_script_recovering();
+                (Some(Program::Script(script)), errors)
+            }
+            (SourceType::Script, false) => fail_fast(self.parse_script().map(Program::Script)),
+            (SourceType::Module, true) => {
+                let (module, errors) = self.parse_module_recovering();
+                (Some(Program::Module(module)), errors)
+            }
+            (SourceType::Module, false) => fail_fast(self.parse_module().map(Program::Module)),
         }
     }
 
     /// Parse a script.
     pub fn parse_script(&mut self) -> Result<Script, ParseError> {
+        self.directive_prologue = true;
         let mut stmts = Vec::new();
+        while let Some(stmt) =
+            self.parse_attaching_comments(Self::parse_statement, Statement::span)?
+        {
+            stmts.push(stmt);
+        }
+        let span = self.span_from(Position::default());
+        let comments = std::mem::take(&mut self.comments);
+        Ok(Script::new(BlockStatement { stmts, span }, comments, span))
+    }
+
+    /// Parse a script, recovering from syntax errors.
+    ///
+    /// Unlike `parse_script`, parsing does not stop at the first syntax error.
+    /// Instead, the erroneous code is represented by `Statement::Error` or
+    /// `Expression::Error` nodes in the returned script and the errors are
+    /// returned alongside it.
+    pub fn parse_script_recovering(&mut self) -> (Script, Vec<ParseError>) {
+        self.recover = true;
+        self.directive_prologue = true;
+        let stmts = self.parse_recovering(Self::parse_statement, Statement::span, Statement::Error);
+        let span = self.span_from(Position::default());
+        let comments = std::mem::take(&mut self.comments);
+        let script = Script::new(BlockStatement { stmts, span }, comments, span);
+        (script, std::mem::take(&mut self.errors))
+    }
+
+    /// Parse a module.
+    pub fn parse_module(&mut self) -> Result<Module, ParseError> {
+        self.module = true;
+        let mut items = Vec::new();
+        while let Some(item) =
+            self.parse_attaching_comments(Self::parse_module_item, ModuleItem::span)?
+        {
+            items.push(item);
+        }
+        let comments = std::mem::take(&mut self.comments);
+        Ok(Module::new(
+            items,
+            comments,
+            self.span_from(Position::default()),
+        ))
+    }
+
+    /// Parse a module, recovering from syntax errors.
+    ///
+    /// See `parse_script_recovering` for details.
+    pub fn parse_module_recovering(&mut self) -> (Module, Vec<ParseError>) {
+        self.module = true;
+        self.recover = true;
+        let items = self.parse_recovering(Self::parse_module_item, ModuleItem::span, |span| {
+            ModuleItem::Statement(Statement::Error(span))
+        });
+        let comments = std::mem::take(&mut self.comments);
+        let module = Module::new(items, comments, self.span_from(Position::default()));
+        (module, std::mem::take(&mut self.errors))
+    }
+
+    /// Parse a list of items with `parse_item` until end of input, replacing
+    /// items that fail to parse with placeholders created by `error_item`.
+    fn parse_recovering<T>(
+        &mut self,
+        parse_item: fn(&mut Self) -> Result<Option<T>, ParseError>,
+        item_span: fn(&T) -> Span,
+        error_item: fn(Span) -> T,
+    ) -> Vec<T> {
+        let mut items = Vec::new();
         loop {
-            match self.parse_statement()? {
-                Some(stmt) => stmts.push(stmt),
-                None => break,
+            let start = self.peek_span().start;
+            match self.parse_attaching_comments(parse_item, item_span) {
+                Ok(Some(item)) => items.push(item),
+                Ok(None) => break,
+                Err(error) => {
+                    // An unexpected token may start the next statement, as
+                    // in `foo(a` followed by `let b` on the next line, so it
+                    // is put back to keep that statement.
+                    if matches!(error.kind, ParseErrorKind::UnexpectedToken { .. })
+                        && self.span.start != start
+                        && self.line_terminator_before
+                        && self.token.as_ref().is_some_and(starts_statement)
+                    {
+                        self.unread_token();
+                    }
+                    self.errors.push(error);
+                    self.synchronize();
+                    // There is no block at the top level for a `}` to close,
+                    // so it belongs to the item that failed to parse.
+                    while self.peek_token() == Some(Token::RightBrace) {
+                        self.next_token();
+                        self.synchronize();
+                    }
+                    items.push(error_item(self.span_from(start)));
+                }
             }
         }
-        Ok(Script::new(BlockStatement { stmts }))
+        items
     }
 
-    /// Parse a statement or a declaration.
-    fn parse_statement(&mut self) -> Result<Option<Statement>, ParseError> {
-        while let Some(token) = self.next_token() {
+    /// Parse an item of a statement list with `parse_item`, and attach the
+    /// comments next to the item to it.
+    ///
+    /// An item that fails to parse gets no comments.
+    fn parse_attaching_comments<T>(
+        &mut self,
+        parse_item: fn(&mut Self) -> Result<Option<T>, ParseError>,
+        item_span: fn(&T) -> Span,
+    ) -> Result<Option<T>, ParseError> {
+        let leading = self.leading_comments();
+        let item = match parse_item(self)? {
+            Some(item) => item,
+            None => return Ok(None),
+        };
+        self.attach_comments(leading, item_span(&item));
+        Ok(Some(item))
+    }
+
+    /// Returns the comments between the previous item and the next token,
+    /// which are leading comments of the item that starts at the token.
+    fn leading_comments(&mut self) -> Range<usize> {
+        // Peek at the first token of the item to read the comments before it.
+        self.peek_token();
+        let leading = self.unattached_comments..self.comments.len();
+        self.unattached_comments = leading.end;
+        leading
+    }
+
+    /// Attach the `leading` comments and the comments after the item at
+    /// `span` on the line where it ends, which are its trailing comments, to
+    /// the item.
+    ///
+    /// This applies to the items of statement lists, and to class elements
+    /// and object literal properties, which are documented like
+    /// declarations.
+    fn attach_comments(&mut self, leading: Range<usize>, span: Span) {
+        for comment in &mut self.comments[leading.clone()] {
+            comment.attachment = Some(CommentAttachment::Leading(span));
+        }
+        // Peek at the token after the item to read the comments before it,
+        // skipping the comments inside the item.
+        self.peek_token();
+        let mut index = leading.end;
+        while index < self.comments.len()
+            && self.comments[index].span.start.offset < span.end.offset
+        {
+            index += 1;
+        }
+        while index < self.comments.len() && self.comments[index].span.start.line == span.end.line {
+            self.comments[index].attachment = Some(CommentAttachment::Trailing(span));
+            index += 1;
+        }
+        self.unattached_comments = self.unattached_comments.max(index);
+    }
+
+    /// Skip tokens until a statement boundary, which is either after a `;`,
+    /// before a `}`, or before a token that starts a statement on a new line.
+    ///
+    /// Braces that are opened while skipping are skipped up to and including
+    /// the matching closing brace.
+    fn synchronize(&mut self) {
+        let mut depth = 0;
+        while let Some(token) = self.peek_token() {
             match token {
-                Token::SingleLineComment => {
-                    if self.preserve_comments {
-                        return self.parse_comment();
-                    }
+                Token::Semicolon if depth == 0 => {
+                    self.next_token();
+                    return;
                 }
-                Token::ImportKeyword => return self.parse_import_declaration(),
-                Token::ConstKeyword | Token::LetKeyword | Token::VarKeyword => {
-                    return self.parse_variable_declaration()
+                Token::LeftBrace => {
+                    depth += 1;
+                    self.next_token();
                 }
+                Token::RightBrace if depth > 0 => {
+                    depth -= 1;
+                    self.next_token();
+                }
+                Token::RightBrace => return,
+                _ if starts_statement(&token) && self.peek_line_terminator() => return,
                 _ => {
-                    return Err(ParseError {
-                        reason: format!("Unexpected token: {}", self.tokenizer.slice()),
-                    })
+                    self.next_token();
                 }
             }
         }
-        Ok(None) // EOF
     }
 
-    fn parse_comment(&mut self) -> Result<Option<Statement>, ParseError> {
-        Ok(Some(Statement::Comment {}))
+    /// Parse a module item, which is an import or export declaration, or a
+    /// statement.
+    fn parse_module_item(&mut self) -> Result<Option<ModuleItem>, ParseError> {
+        match self.peek_token() {
+            Some(Token::ImportKeyword) if !self.peek_import_expression() => {
+                self.next_token();
+                let decl = self.parse_import_declaration()?;
+                Ok(Some(ModuleItem::ImportDeclaration(decl)))
+            }
+            Some(Token::ExportKeyword) => {
+                self.next_token();
+                let decl = self.parse_export_declaration()?;
+                Ok(Some(ModuleItem::ExportDeclaration(decl)))
+            }
+            _ => Ok(self.parse_statement()?.map(ModuleItem::Statement)),
+        }
     }
 
-    // Parse an import declaration.
-    fn parse_import_declaration(&mut self) -> Result<Option<Statement>, ParseError> {
-        if let Some(import_clause) = self.parse_import_clause()? {
-            if let Some(from_clause) = self.parse_from_clause()? {
-                return Ok(Some(Statement::ImportDeclaration(ImportDeclaration {
-                    import_clause: Some(import_clause),
-                    module_specifier: from_clause,
-                })));
-            } else {
-                return Err(ParseError {
-                    reason: "Expression expected.".to_string(),
-                });
+    /// Parse a statement or a declaration.
+    fn parse_statement(&mut self) -> Result<Option<Statement>, ParseError> {
+        // The statements nested in this one are never directives.
+        let directive_prologue = std::mem::take(&mut self.directive_prologue);
+        match self.peek_token() {
+            Some(Token::ImportKeyword) if self.peek_import_expression() => {
+                self.parse_expression_statement().map(Some)
+            }
+            Some(token @ (Token::ExportKeyword | Token::ImportKeyword)) => {
+                self.next_token();
+                let kind = if self.module {
+                    ParseErrorKind::ModuleDeclarationNotAtTopLevel
+                } else {
+                    ParseErrorKind::ModuleDeclarationInScript
+                };
+                Err(self.error(Some(token), kind))
+            }
+            Some(Token::ConstKeyword | Token::LetKeyword | Token::VarKeyword) => {
+                self.next_token();
+                let stmt = self.parse_variable_statement()?;
+                Ok(Some(Statement::VariableStatement(stmt)))
+            }
+            _ if self.peek_using_declaration() => {
+                self.next_token();
+                let stmt = self.parse_variable_statement()?;
+                Ok(Some(Statement::VariableStatement(stmt)))
+            }
+            Some(Token::LeftBrace) => Ok(Some(Statement::BlockStatement(
+                self.parse_block_statement()?,
+            ))),
+            Some(Token::Semicolon) => {
+                self.next_token();
+                Ok(Some(Statement::EmptyStatement(self.span)))
             }
+            Some(Token::ClassKeyword) => {
+                Ok(Some(Statement::ClassDeclaration(self.parse_class(true)?)))
+            }
+            Some(Token::BreakKeyword) => self.parse_break_statement().map(Some),
+            Some(Token::ContinueKeyword) => self.parse_continue_statement().map(Some),
+            Some(Token::DebuggerKeyword) => {
+                self.next_token();
+                let start = self.span.start;
+                self.parse_semicolon()?;
+                Ok(Some(Statement::DebuggerStatement(self.span_from(start))))
+            }
+            Some(Token::DoKeyword) => self.parse_do_while_statement().map(Some),
+            Some(Token::ForKeyword) => self.parse_for_statement().map(Some),
+            Some(Token::IfKeyword) => self.parse_if_statement().map(Some),
+            Some(Token::ReturnKeyword) => self.parse_return_statement().map(Some),
+            Some(Token::SwitchKeyword) => self.parse_switch_statement().map(Some),
+            Some(Token::ThrowKeyword) => self.parse_throw_statement().map(Some),
+            Some(Token::TryKeyword) => self.parse_try_statement().map(Some),
+            Some(Token::WhileKeyword) => self.parse_while_statement().map(Some),
+            Some(Token::WithKeyword) => self.parse_with_statement().map(Some),
+            Some(Token::IdentifierName)
+                if matches!(self.peek_second_token(), Some((Token::Colon, _))) =>
+            {
+                self.parse_labeled_statement().map(Some)
+            }
+            _ if self.peek_function_declaration() => {
+                let function = self.parse_function_declaration(true)?;
+                Ok(Some(Statement::FunctionDeclaration(function)))
+            }
+            Some(Token::StringLiteral) if directive_prologue => self.parse_directive().map(Some),
+            Some(_) => self.parse_expression_statement().map(Some),
+            None => Ok(None), // EOF
         }
-        if let Some(module_specifier) = self.parse_module_specifier()? {
-            return Ok(Some(Statement::ImportDeclaration(ImportDeclaration {
-                import_clause: None,
-                module_specifier,
-            })));
+    }
+
+    /// Parse an expression statement in a directive prologue that starts
+    /// with a string literal. If the string literal is the whole expression,
+    /// the statement is a directive, and the directive prologue continues.
+    ///
+    /// A `"use strict"` directive, which must be written without escape
+    /// sequences, makes the rest of the script or function strict mode code.
+    fn parse_directive(&mut self) -> Result<Statement, ParseError> {
+        let stmt = self.parse_expression_statement()?;
+        if let Statement::ExpressionStatement(ExpressionStatement {
+            expression: Expression::StringLiteral(literal),
+            ..
+        }) = &stmt
+        {
+            if literal.raw[1..literal.raw.len() - 1] == *"use strict" {
+                if self.context.non_simple_parameters {
+                    return Err(ParseError::new(
+                        ParseErrorKind::UseStrictWithNonSimpleParameters,
+                        literal.span,
+                    ));
+                }
+                self.use_strict = true;
+            }
+            self.directive_prologue = true;
         }
-        Err(ParseError {
-            reason: "Declaration or statement expected.".to_string(),
+        Ok(stmt)
+    }
+
+    /// Parse a block statement, which is also the body of a function.
+    fn parse_block_statement(&mut self) -> Result<BlockStatement, ParseError> {
+        self.expect_token(Token::LeftBrace)?;
+        let start = self.span.start;
+        let stmts = self.with_allow_in(true, |parser| {
+            parser.parse_statement_list(|token| token == Some(Token::RightBrace))
+        })?;
+        self.next_token();
+        Ok(BlockStatement {
+            stmts,
+            span: self.span_from(start),
         })
     }
 
-    fn parse_import_clause(&mut self) -> Result<Option<ImportClause>, ParseError> {
-        match self.peek_token() {
-            Some(Token::LeftBrace) => self.parse_named_imports(),
-            _ => Ok(None),
+    /// Parse statements until the next token is one for which `is_end`
+    /// returns true. The end token is not consumed.
+    fn parse_statement_list(
+        &mut self,
+        is_end: fn(Option<Token>) -> bool,
+    ) -> Result<Vec<Statement>, ParseError> {
+        let mut stmts = Vec::new();
+        while !is_end(self.peek_token()) {
+            match self.parse_attaching_comments(Self::parse_statement, Statement::span)? {
+                Some(stmt) => stmts.push(stmt),
+                None => return Err(self.unexpected_token(None, vec![Token::RightBrace])),
+            }
         }
+        Ok(stmts)
     }
 
-    fn parse_named_imports(&mut self) -> Result<Option<ImportClause>, ParseError> {
-        self.expect_token(Token::LeftBrace)?;
-        let mut import_specifiers = vec![];
-        loop {
-            match self.next_token() {
-                Some(Token::IdentifierName) => {
-                    let import_specifier = self.tokenizer.slice().to_string();
-                    import_specifiers.push(import_specifier);
+    /// Parse the body of a function with `parse`, outside of the statements
+    /// that enclose the function.
+    ///
+    /// `await` and `yield` are operators in the body if the function is
+    /// async or a generator, respectively. A block body starts with a
+    /// directive prologue, whose `"use strict"` directive applies only to
+    /// the body.
+    fn parse_function_body<T>(
+        &mut self,
+        params: &[Pattern],
+        is_async: bool,
+        is_generator: bool,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        let context = StatementContext {
+            in_function: true,
+            in_async: is_async,
+            in_generator: is_generator,
+            non_simple_parameters: !is_simple_parameter_list(params),
+            allow_new_target: self.context.allow_new_target,
+            allow_super_property: self.context.allow_super_property,
+            allow_super_call: self.context.allow_super_call,
+            ..StatementContext::default()
+        };
+        let use_strict = self.use_strict;
+        self.directive_prologue = true;
+        let result = self.with_context(context, |parser| {
+            let body = parse(parser)?;
+            // The parameters are parsed before a `"use strict"` directive in
+            // the body can make the function strict, so check them again.
+            if parser.is_strict() {
+                check_duplicate_parameters(params)?;
+                let mut names = Vec::new();
+                for param in params {
+                    bound_names(param, &mut names);
                 }
-                Some(Token::Comma) => continue,
-                Some(Token::RightBrace) => break,
-                _ => {
-                    return Err(ParseError {
-                        reason: "Identifier expected".to_string(),
-                    })
+                for name in names {
+                    parser.check_binding_identifier(name)?;
                 }
             }
+            Ok(body)
+        });
+        self.directive_prologue = false;
+        self.use_strict = use_strict;
+        result
+    }
+
+    /// Parse with `parse` as part of a non-arrow function or a class element,
+    /// where `new.target` is allowed, and `super` property accesses and
+    /// calls are allowed if `super_property` and `super_call` are set.
+    fn with_function_context<T>(
+        &mut self,
+        super_property: bool,
+        super_call: bool,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        let saved = (
+            self.context.allow_new_target,
+            self.context.allow_super_property,
+            self.context.allow_super_call,
+        );
+        self.context.allow_new_target = true;
+        self.context.allow_super_property = super_property;
+        self.context.allow_super_call = super_call;
+        let result = parse(self);
+        (
+            self.context.allow_new_target,
+            self.context.allow_super_property,
+            self.context.allow_super_call,
+        ) = saved;
+        result
+    }
+
+    /// Parse with `parse` in `context`, and restore the current context
+    /// afterwards.
+    fn with_context<T>(
+        &mut self,
+        context: StatementContext,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        let context = std::mem::replace(&mut self.context, context);
+        let result = parse(self);
+        self.context = context;
+        result
+    }
+
+    /// Parse with `parse`, with `in` allowed as a binary operator or not,
+    /// and restore the current setting afterwards.
+    fn with_allow_in<T>(
+        &mut self,
+        allow_in: bool,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        let allow_in = std::mem::replace(&mut self.allow_in, allow_in);
+        let result = parse(self);
+        self.allow_in = allow_in;
+        result
+    }
+
+    /// Parse the body of a compound statement, such as an `if` statement.
+    fn parse_substatement(&mut self) -> Result<Statement, ParseError> {
+        match self.parse_statement()? {
+            Some(stmt) => Ok(stmt),
+            None => Err(self.expected(ParseErrorKind::DeclarationOrStatementExpected)),
+        }
+    }
+
+    /// Parse the body of a loop.
+    fn parse_loop_body(&mut self) -> Result<Statement, ParseError> {
+        let in_iteration = std::mem::replace(&mut self.context.in_iteration, true);
+        let body = self.parse_substatement();
+        self.context.in_iteration = in_iteration;
+        body
+    }
+
+    /// Parse a parenthesized expression that follows a keyword, such as the
+    /// condition of an `if` statement.
+    fn parse_parenthesized_head(&mut self) -> Result<Expression, ParseError> {
+        self.expect_token(Token::LeftParenthesis)?;
+        let expression = self.parse_sequence_expression()?;
+        self.expect_token(Token::RightParenthesis)?;
+        Ok(expression)
+    }
+
+    /// Parse an expression that is required.
+    fn parse_expression(&mut self) -> Result<Expression, ParseError> {
+        match self.parse_assignment_expression()? {
+            Some(expression) => Ok(expression),
+            None => self.expression_expected(),
+        }
+    }
+
+    /// Parse an expression that is required, which may be a sequence of
+    /// comma-separated expressions.
+    fn parse_sequence_expression(&mut self) -> Result<Expression, ParseError> {
+        let expression = self.parse_expression()?;
+        self.parse_sequence_rest(expression)
+    }
+
+    /// Parse the rest of a sequence expression after its first expression,
+    /// if a `,` follows it.
+    fn parse_sequence_rest(&mut self, first: Expression) -> Result<Expression, ParseError> {
+        if self.peek_token() != Some(Token::Comma) {
+            return Ok(first);
+        }
+        let start = first.span().start;
+        let mut expressions = vec![first];
+        while self.peek_token() == Some(Token::Comma) {
+            self.next_token();
+            expressions.push(self.parse_expression()?);
+        }
+        Ok(Expression::SequenceExpression(SequenceExpression {
+            expressions,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Returns true if the statement being parsed ends before the next
+    /// token, which is the case before a `;`, a `}`, the end of input, or a
+    /// token on a new line.
+    fn peek_statement_end(&mut self) -> bool {
+        matches!(
+            self.peek_token(),
+            None | Some(Token::Semicolon | Token::RightBrace)
+        ) || self.peek_line_terminator()
+    }
+
+    /// Parse a `break` statement.
+    fn parse_break_statement(&mut self) -> Result<Statement, ParseError> {
+        self.expect_token(Token::BreakKeyword)?;
+        let start = self.span.start;
+        let keyword_span = self.span;
+        let label = self.parse_jump_label()?;
+        match &label {
+            Some(label) if !self.context.labels.iter().any(|l| l.name == label.value) => {
+                return Err(ParseError::new(
+                    ParseErrorKind::UndefinedLabel(label.value.clone()),
+                    label.span,
+                ));
+            }
+            None if !self.context.in_iteration && !self.context.in_switch => {
+                return Err(ParseError::new(ParseErrorKind::InvalidBreak, keyword_span));
+            }
+            _ => {}
+        }
+        if label.is_some() || !self.peek_line_terminator() {
+            self.parse_semicolon()?;
         }
-        Ok(Some(ImportClause::NamedImports(import_specifiers)))
+        Ok(Statement::BreakStatement(BreakStatement {
+            label,
+            span: self.span_from(start),
+        }))
     }
 
-    fn parse_from_clause(&mut self) -> Result<Option<ModuleSpecifier>, ParseError> {
-        if let Some(_) = self.next_token() {
-            // FIXME: The underlying tokenizer could have advanced to a different token.
-            // Let's fix this by storing the slice as part of the lookahead.
-            if self.tokenizer.slice() != "from" {
-                return Err(ParseError {
-                    reason: "`from` expected".to_string(),
-                });
+    /// Parse a `continue` statement.
+    fn parse_continue_statement(&mut self) -> Result<Statement, ParseError> {
+        self.expect_token(Token::ContinueKeyword)?;
+        let start = self.span.start;
+        let keyword_span = self.span;
+        let label = self.parse_jump_label()?;
+        if !self.context.in_iteration {
+            return Err(ParseError::new(
+                ParseErrorKind::InvalidContinue,
+                keyword_span,
+            ));
+        }
+        if let Some(label) = &label {
+            match self.context.labels.iter().find(|l| l.name == label.value) {
+                Some(target) if target.iteration => {}
+                Some(_) => {
+                    return Err(ParseError::new(ParseErrorKind::InvalidContinue, label.span))
+                }
+                None => {
+                    return Err(ParseError::new(
+                        ParseErrorKind::UndefinedLabel(label.value.clone()),
+                        label.span,
+                    ))
+                }
+            }
+        }
+        if label.is_some() || !self.peek_line_terminator() {
+            self.parse_semicolon()?;
+        }
+        Ok(Statement::ContinueStatement(ContinueStatement {
+            label,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Parse the optional label of a `break` or `continue` statement, which
+    /// must be on the same line as the keyword. A line terminator after the
+    /// keyword ends the statement, so a `;` on the next line is an empty
+    /// statement.
+    fn parse_jump_label(&mut self) -> Result<Option<Identifier>, ParseError> {
+        if self.peek_line_terminator() {
+            return Ok(None);
+        }
+        self.parser_binding_identifier()
+    }
+
+    /// Parse a `do`-`while` statement.
+    fn parse_do_while_statement(&mut self) -> Result<Statement, ParseError> {
+        self.expect_token(Token::DoKeyword)?;
+        let start = self.span.start;
+        let body = self.parse_loop_body()?;
+        self.expect_token(Token::WhileKeyword)?;
+        let test = self.parse_parenthesized_head()?;
+        // A `;` is inserted after a `do`-`while` statement even if the next
+        // token is on the same line.
+        self.parse_optional_semicolon();
+        Ok(Statement::DoWhileStatement(Box::new(DoWhileStatement {
+            body,
+            test,
+            span: self.span_from(start),
+        })))
+    }
+
+    /// Parse a `for`, `for`-`in`, `for`-`of` or `for await` statement.
+    fn parse_for_statement(&mut self) -> Result<Statement, ParseError> {
+        self.expect_token(Token::ForKeyword)?;
+        let start = self.span.start;
+        let is_await =
+            self.is_await_operator() && self.peek_contextual_keyword(ContextualKeyword::Await);
+        if is_await {
+            self.next_token();
+            self.expect_ecma_version("`for await` statements", EcmaVersion::Es2018)?;
+        }
+        self.expect_token(Token::LeftParenthesis)?;
+        // Annex B allows an initializer in `for (var a = b in c)` in sloppy
+        // mode code.
+        let mut allow_initializer = false;
+        let mut pattern_error = None;
+        let init = match self.peek_token() {
+            Some(Token::Semicolon) if !is_await => None,
+            Some(Token::ConstKeyword | Token::LetKeyword | Token::VarKeyword) => {
+                allow_initializer =
+                    self.next_token() == Some(Token::VarKeyword) && !self.is_strict();
+                let declaration = self.with_allow_in(false, Self::parse_variable_declaration)?;
+                Some(ForInit::VariableStatement(declaration))
             }
-            self.parse_module_specifier()
+            _ if self.peek_using_declaration() => {
+                self.next_token();
+                let declaration = self.with_allow_in(false, Self::parse_variable_declaration)?;
+                Some(ForInit::VariableStatement(declaration))
+            }
+            _ => {
+                let (expression, error) = self.parse_cover(|parser| {
+                    parser.with_allow_in(false, |parser| {
+                        let expression = parser.parse_cover_expression()?;
+                        parser.parse_sequence_rest(expression)
+                    })
+                })?;
+                pattern_error = error;
+                Some(ForInit::Expression(expression))
+            }
+        };
+        let is_of = self.peek_contextual_keyword(ContextualKeyword::Of);
+        if is_await && !is_of {
+            let found = self.next_token();
+            return Err(self.unexpected_token(found, vec![]));
+        }
+        let init = match init {
+            Some(init) if is_of || self.peek_token() == Some(Token::InKeyword) => {
+                let left = self.for_in_of_left(init, allow_initializer && !is_of)?;
+                return self.parse_for_in_of_statement(start, left, is_of, is_await);
+            }
+            init => init,
+        };
+        if let Some(error) = pattern_error {
+            return Err(error);
+        }
+        if let Some(ForInit::VariableStatement(declaration)) = &init {
+            check_initializers(declaration)?;
+        }
+        self.expect_token(Token::Semicolon)?;
+        let test = match self.peek_token() {
+            Some(Token::Semicolon) => None,
+            _ => Some(self.parse_sequence_expression()?),
+        };
+        self.expect_token(Token::Semicolon)?;
+        let update = match self.peek_token() {
+            Some(Token::RightParenthesis) => None,
+            _ => Some(self.parse_sequence_expression()?),
+        };
+        self.expect_token(Token::RightParenthesis)?;
+        let body = self.parse_loop_body()?;
+        Ok(Statement::ForStatement(Box::new(ForStatement {
+            init,
+            test,
+            update,
+            body,
+            span: self.span_from(start),
+        })))
+    }
+
+    /// Reinterprets the initialization part of a `for` statement as the
+    /// left-hand side of a `for`-`in` or `for`-`of` statement.
+    fn for_in_of_left(
+        &self,
+        init: ForInit,
+        allow_initializer: bool,
+    ) -> Result<ForInOfLeft, ParseError> {
+        match init {
+            // An assignment is not an assignment target, even though it is
+            // reinterpreted as a pattern with a default value elsewhere.
+            ForInit::Expression(Expression::AssignmentExpression(assignment)) => Err(
+                ParseError::new(ParseErrorKind::InvalidAssignmentTarget, assignment.span),
+            ),
+            ForInit::Expression(expression) => Ok(ForInOfLeft::Pattern(
+                self.expression_to_pattern(expression, false)?,
+            )),
+            ForInit::VariableStatement(declaration) => match declaration.declarations.as_slice() {
+                [declarator] => match (&declarator.id, &declarator.initializer) {
+                    (Pattern::Identifier(_), Some(_)) if allow_initializer => {
+                        Ok(ForInOfLeft::VariableStatement(declaration))
+                    }
+                    (_, Some(initializer)) => Err(ParseError::new(
+                        ParseErrorKind::ForInOfInitializer,
+                        initializer.span(),
+                    )),
+                    (_, None) => Ok(ForInOfLeft::VariableStatement(declaration)),
+                },
+                _ => Err(ParseError::new(
+                    ParseErrorKind::ForInOfMultipleDeclarations,
+                    declaration.span,
+                )),
+            },
+        }
+    }
+
+    /// Parse the rest of a `for`-`in` or `for`-`of` statement from the `in`
+    /// or `of` keyword.
+    fn parse_for_in_of_statement(
+        &mut self,
+        start: Position,
+        left: ForInOfLeft,
+        is_of: bool,
+        is_await: bool,
+    ) -> Result<Statement, ParseError> {
+        self.next_token();
+        if is_of {
+            self.expect_ecma_version("`for`-`of` statements", EcmaVersion::Es2015)?;
+        }
+        // The right-hand side of a `for`-`of` statement is a single
+        // assignment expression, so `for (a of b, c) {}` is not allowed.
+        let right = if is_of {
+            self.parse_expression()?
         } else {
-            Err(ParseError {
-                reason: "`from` expected".to_string(),
-            })
+            self.parse_sequence_expression()?
+        };
+        self.expect_token(Token::RightParenthesis)?;
+        let body = self.parse_loop_body()?;
+        let span = self.span_from(start);
+        if is_of {
+            Ok(Statement::ForOfStatement(Box::new(ForOfStatement {
+                left,
+                right,
+                body,
+                is_await,
+                span,
+            })))
+        } else {
+            Ok(Statement::ForInStatement(Box::new(ForInStatement {
+                left,
+                right,
+                body,
+                span,
+            })))
         }
     }
 
-    fn parse_module_specifier(&mut self) -> Result<Option<ModuleSpecifier>, ParseError> {
-        if let Some(Token::StringLiteral) = self.peek_token() {
-            self.expect_token(Token::StringLiteral)?;
-            Ok(Some(ModuleSpecifier {
-                value: self.tokenizer.slice().to_string(),
-            }))
+    /// Parse an `if` statement.
+    fn parse_if_statement(&mut self) -> Result<Statement, ParseError> {
+        self.expect_token(Token::IfKeyword)?;
+        let start = self.span.start;
+        let test = self.parse_parenthesized_head()?;
+        let consequent = self.parse_substatement()?;
+        let alternate = if self.peek_token() == Some(Token::ElseKeyword) {
+            self.next_token();
+            Some(self.parse_substatement()?)
         } else {
-            Ok(None)
+            None
+        };
+        Ok(Statement::IfStatement(Box::new(IfStatement {
+            test,
+            consequent,
+            alternate,
+            span: self.span_from(start),
+        })))
+    }
+
+    /// Parse a labeled statement, including the labels of directly nested
+    /// labeled statements, as in `a: b: c;`.
+    fn parse_labeled_statement(&mut self) -> Result<Statement, ParseError> {
+        let depth = self.context.labels.len();
+        let stmt = self.parse_labels_and_body(depth);
+        self.context.labels.truncate(depth);
+        stmt
+    }
+
+    /// Parse the labels of a labeled statement and its body. The labels are
+    /// pushed on top of the `depth` labels of the enclosing statements.
+    fn parse_labels_and_body(&mut self, depth: usize) -> Result<Statement, ParseError> {
+        let mut labels = Vec::new();
+        while self.peek_token() == Some(Token::IdentifierName)
+            && matches!(self.peek_second_token(), Some((Token::Colon, _)))
+        {
+            self.next_token();
+            let label = self.identifier();
+            self.next_token();
+            if self.context.labels.iter().any(|l| l.name == label.value) {
+                return Err(ParseError::new(
+                    ParseErrorKind::DuplicateLabel(label.value),
+                    label.span,
+                ));
+            }
+            self.context.labels.push(Label {
+                name: label.value.clone(),
+                iteration: false,
+            });
+            labels.push(label);
         }
+        let iteration = matches!(
+            self.peek_token(),
+            Some(Token::DoKeyword | Token::ForKeyword | Token::WhileKeyword)
+        );
+        for label in &mut self.context.labels[depth..] {
+            label.iteration = iteration;
+        }
+        let mut body = self.parse_substatement()?;
+        for label in labels.into_iter().rev() {
+            let span = Span::new(label.span.start, body.span().end);
+            body = Statement::LabeledStatement(Box::new(LabeledStatement { label, body, span }));
+        }
+        Ok(body)
     }
 
-    // Parse a variable declaration.
-    fn parse_variable_declaration(&mut self) -> Result<Option<Statement>, ParseError> {
-        if let Some(binding_identifier) = self.parser_binding_identifier()? {
-            let initializer = self.parse_initializer()?;
-            return Ok(Some(Statement::VariableStatement(VariableStatement {
-                binding_identifier,
-                initializer,
-            })));
+    /// Parse a `return` statement.
+    fn parse_return_statement(&mut self) -> Result<Statement, ParseError> {
+        self.expect_token(Token::ReturnKeyword)?;
+        let start = self.span.start;
+        if !self.context.in_function {
+            return Err(ParseError::new(
+                ParseErrorKind::ReturnOutsideFunction,
+                self.span,
+            ));
+        }
+        let argument = if self.peek_statement_end() {
+            None
+        } else {
+            Some(self.parse_sequence_expression()?)
+        };
+        // A line terminator after `return` ends the statement, so a `;` on
+        // the next line is an empty statement.
+        if argument.is_some() || !self.peek_line_terminator() {
+            self.parse_semicolon()?;
         }
-        todo!("Unexpected token: `{:?}`", self.next_token());
+        Ok(Statement::ReturnStatement(ReturnStatement {
+            argument,
+            span: self.span_from(start),
+        }))
     }
 
-    fn parser_binding_identifier(&mut self) -> Result<Option<Identifier>, ParseError> {
-        match self.peek_token() {
-            Some(Token::IdentifierName) => {
-                self.next_token();
-                Ok(Some(Identifier {
-                    value: self.tokenizer.slice().to_string(),
-                }))
+    /// Parse a `switch` statement.
+    fn parse_switch_statement(&mut self) -> Result<Statement, ParseError> {
+        self.expect_token(Token::SwitchKeyword)?;
+        let start = self.span.start;
+        let discriminant = self.parse_parenthesized_head()?;
+        self.expect_token(Token::LeftBrace)?;
+        let in_switch = std::mem::replace(&mut self.context.in_switch, true);
+        let cases = self.parse_switch_cases();
+        self.context.in_switch = in_switch;
+        Ok(Statement::SwitchStatement(SwitchStatement {
+            discriminant,
+            cases: cases?,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Parse the `case` and `default` clauses of a `switch` statement up to
+    /// and including the closing `}`.
+    fn parse_switch_cases(&mut self) -> Result<Vec<SwitchCase>, ParseError> {
+        let mut cases = Vec::new();
+        let mut has_default = false;
+        loop {
+            let start = self.peek_span().start;
+            let test = match self.next_token() {
+                Some(Token::RightBrace) => break,
+                Some(Token::CaseKeyword) => Some(self.parse_sequence_expression()?),
+                Some(Token::DefaultKeyword) => {
+                    if has_default {
+                        return Err(ParseError::new(
+                            ParseErrorKind::DuplicateDefaultClause,
+                            self.span,
+                        ));
+                    }
+                    has_default = true;
+                    None
+                }
+                found => {
+                    return Err(self.unexpected_token(
+                        found,
+                        vec![Token::CaseKeyword, Token::DefaultKeyword, Token::RightBrace],
+                    ))
+                }
+            };
+            self.expect_token(Token::Colon)?;
+            let consequent = self.parse_statement_list(|token| {
+                matches!(
+                    token,
+                    Some(Token::CaseKeyword | Token::DefaultKeyword | Token::RightBrace)
+                )
+            })?;
+            cases.push(SwitchCase {
+                test,
+                consequent,
+                span: self.span_from(start),
+            });
+        }
+        Ok(cases)
+    }
+
+    /// Parse a `throw` statement.
+    fn parse_throw_statement(&mut self) -> Result<Statement, ParseError> {
+        self.expect_token(Token::ThrowKeyword)?;
+        let start = self.span.start;
+        if self.peek_line_terminator() {
+            return Err(ParseError::new(
+                ParseErrorKind::LineBreakNotPermitted,
+                self.span,
+            ));
+        }
+        let argument = self.parse_sequence_expression()?;
+        self.parse_semicolon()?;
+        Ok(Statement::ThrowStatement(ThrowStatement {
+            argument,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Parse a `try` statement.
+    fn parse_try_statement(&mut self) -> Result<Statement, ParseError> {
+        self.expect_token(Token::TryKeyword)?;
+        let start = self.span.start;
+        let block = self.parse_block_statement()?;
+        let handler = if self.peek_token() == Some(Token::CatchKeyword) {
+            Some(self.parse_catch_clause()?)
+        } else {
+            None
+        };
+        let finalizer = if self.peek_token() == Some(Token::FinallyKeyword) {
+            self.next_token();
+            Some(self.parse_block_statement()?)
+        } else {
+            None
+        };
+        if handler.is_none() && finalizer.is_none() {
+            let found = self.next_token();
+            return Err(
+                self.unexpected_token(found, vec![Token::CatchKeyword, Token::FinallyKeyword])
+            );
+        }
+        Ok(Statement::TryStatement(TryStatement {
+            block,
+            handler,
+            finalizer,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Parse a `catch` clause, whose parameter is optional.
+    fn parse_catch_clause(&mut self) -> Result<CatchClause, ParseError> {
+        self.expect_token(Token::CatchKeyword)?;
+        let start = self.span.start;
+        let param = if self.peek_token() == Some(Token::LeftParenthesis) {
+            self.next_token();
+            let param = self.parse_binding_pattern()?;
+            self.expect_token(Token::RightParenthesis)?;
+            Some(param)
+        } else {
+            self.expect_ecma_version("Optional catch bindings", EcmaVersion::Es2019)?;
+            None
+        };
+        let body = self.parse_block_statement()?;
+        Ok(CatchClause {
+            param,
+            body,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse a `while` statement.
+    fn parse_while_statement(&mut self) -> Result<Statement, ParseError> {
+        self.expect_token(Token::WhileKeyword)?;
+        let start = self.span.start;
+        let test = self.parse_parenthesized_head()?;
+        let body = self.parse_loop_body()?;
+        Ok(Statement::WhileStatement(Box::new(WhileStatement {
+            test,
+            body,
+            span: self.span_from(start),
+        })))
+    }
+
+    /// Parse a `with` statement, which is not allowed in strict mode code.
+    fn parse_with_statement(&mut self) -> Result<Statement, ParseError> {
+        self.expect_token(Token::WithKeyword)?;
+        let start = self.span.start;
+        if self.is_strict() {
+            return Err(ParseError::new(ParseErrorKind::WithInStrictMode, self.span));
+        }
+        let object = self.parse_parenthesized_head()?;
+        let body = self.parse_substatement()?;
+        Ok(Statement::WithStatement(Box::new(WithStatement {
+            object,
+            body,
+            span: self.span_from(start),
+        })))
+    }
+
+    fn parse_expression_statement(&mut self) -> Result<Statement, ParseError> {
+        let start = self.peek_span().start;
+        let expression = match self.parse_assignment_expression()? {
+            Some(expression) => self.parse_sequence_rest(expression)?,
+            None => {
+                let token = self.next_token();
+                return Err(self.unexpected_token(token, vec![]));
             }
-            _ => Ok(None),
+        };
+        self.parse_semicolon()?;
+        Ok(Statement::ExpressionStatement(ExpressionStatement {
+            expression,
+            span: self.span_from(start),
+        }))
+    }
+
+    // Parse an import declaration.
+    fn parse_import_declaration(&mut self) -> Result<ImportDeclaration, ParseError> {
+        let start = self.span.start;
+        let (type_only, default_binding) = self.parse_import_type_modifier();
+        if type_only {
+            self.expect_typescript("`import type` declarations")?;
         }
+        let (import_clause, module_specifier) = match self.parse_import_clause(default_binding)? {
+            Some(import_clause) => match self.parse_from_clause()? {
+                Some(module_specifier) => (Some(import_clause), module_specifier),
+                None => {
+                    let found = self.next_token();
+                    return Err(self.unexpected_token(found, vec![Token::StringLiteral]));
+                }
+            },
+            None => match self.parse_module_specifier()? {
+                Some(module_specifier) => (None, module_specifier),
+                None => return Err(self.expected(ParseErrorKind::DeclarationOrStatementExpected)),
+            },
+        };
+        let attributes = self.parse_with_clause()?;
+        self.parse_semicolon()?;
+        Ok(ImportDeclaration {
+            import_clause,
+            module_specifier,
+            attributes,
+            type_only,
+            span: self.span_from(start),
+        })
     }
 
-    fn parse_initializer(&mut self) -> Result<Option<Expression>, ParseError> {
+    /// Parse the TypeScript `type` modifier of a type-only import.
+    ///
+    /// In `import type from "m"` and `import type, { a } from "m"`, `type` is
+    /// not a modifier but the default binding, which is returned instead.
+    fn parse_import_type_modifier(&mut self) -> (bool, Option<Identifier>) {
+        if !self.peek_contextual_keyword(ContextualKeyword::Type) {
+            return (false, None);
+        }
+        self.next_token();
+        let type_binding = self.identifier();
         match self.peek_token() {
-            Some(Token::Assignment) => {
+            Some(Token::LeftBrace) | Some(Token::Asterisk) => (true, None),
+            Some(Token::IdentifierName)
+                if !self.peek_contextual_keyword(ContextualKeyword::From) =>
+            {
+                (true, None)
+            }
+            _ => (false, Some(type_binding)),
+        }
+    }
+
+    /// Parse an import clause, if there is one.
+    ///
+    /// If the default binding has already been consumed, it is passed in as
+    /// `default_binding`.
+    fn parse_import_clause(
+        &mut self,
+        default_binding: Option<Identifier>,
+    ) -> Result<Option<ImportClause>, ParseError> {
+        let default_binding = match default_binding {
+            Some(default_binding) => Some(default_binding),
+            None => self.parser_binding_identifier()?,
+        };
+        let start = match &default_binding {
+            Some(default_binding) => default_binding.span.start,
+            None => self.peek_span().start,
+        };
+        let named_bindings = match &default_binding {
+            Some(_) if self.peek_token() != Some(Token::Comma) => None,
+            Some(_) => {
                 self.next_token();
-                self.parse_assignment_expression()
+                match self.peek_token() {
+                    Some(Token::Asterisk) | Some(Token::LeftBrace) => {
+                        self.parse_named_bindings()?
+                    }
+                    _ => {
+                        let found = self.next_token();
+                        return Err(
+                            self.unexpected_token(found, vec![Token::Asterisk, Token::LeftBrace])
+                        );
+                    }
+                }
             }
+            None => match self.parse_named_bindings()? {
+                Some(named_bindings) => Some(named_bindings),
+                None => return Ok(None),
+            },
+        };
+        Ok(Some(ImportClause {
+            default_binding,
+            named_bindings,
+            span: self.span_from(start),
+        }))
+    }
+
+    fn parse_named_bindings(&mut self) -> Result<Option<NamedBindings>, ParseError> {
+        match self.peek_token() {
+            Some(Token::Asterisk) => self.parse_namespace_import(),
+            Some(Token::LeftBrace) => self.parse_named_imports(),
             _ => Ok(None),
         }
     }
 
-    fn parse_assignment_expression(&mut self) -> Result<Option<Expression>, ParseError> {
-        todo!();
+    fn parse_namespace_import(&mut self) -> Result<Option<NamedBindings>, ParseError> {
+        self.expect_token(Token::Asterisk)?;
+        if !self.peek_contextual_keyword(ContextualKeyword::As) {
+            return Err(self.expected(ParseErrorKind::MissingAs));
+        }
+        self.next_token();
+        match self.parser_binding_identifier()? {
+            Some(local) => Ok(Some(NamedBindings::NamespaceImport(local))),
+            None => Err(self.expected(ParseErrorKind::IdentifierExpected)),
+        }
     }
 
-    fn peek_token(&mut self) -> Option<Token> {
-        if let Ok(lookahead) = self.lookahead.try_borrow() {
-            let lookahead = (*lookahead).clone();
-            if let Some(lookahead) = lookahead {
-                return Some(lookahead);
+    fn parse_named_imports(&mut self) -> Result<Option<NamedBindings>, ParseError> {
+        self.expect_token(Token::LeftBrace)?;
+        let mut import_specifiers = vec![];
+        loop {
+            if let Some(Token::RightBrace) = self.peek_token() {
+                self.next_token();
+                break;
+            }
+            import_specifiers.push(self.parse_import_specifier()?);
+            match self.next_token() {
+                Some(Token::Comma) => continue,
+                Some(Token::RightBrace) => break,
+                found => {
+                    return Err(self.unexpected_token(found, vec![Token::Comma, Token::RightBrace]))
+                }
             }
         }
-        let token = self.tokenizer.next_token();
-        self.lookahead.replace(token.clone());
-        token
+        Ok(Some(NamedBindings::NamedImports(import_specifiers)))
     }
 
-    fn expect_token(&mut self, expected: Token) -> Result<(), ParseError> {
+    fn parse_import_specifier(&mut self) -> Result<ImportSpecifier, ParseError> {
+        let start = self.peek_span().start;
+        let (type_only, type_name) = self.parse_specifier_type_modifier()?;
+        let imported = match type_name {
+            Some(type_name) => type_name,
+            None => {
+                let is_string = self.peek_token() == Some(Token::StringLiteral);
+                let imported = self.parse_module_export_name()?;
+                // A string name must be renamed to an identifier with `as`.
+                if is_string && !self.peek_contextual_keyword(ContextualKeyword::As) {
+                    return Err(self.error(
+                        Some(Token::StringLiteral),
+                        ParseErrorKind::IdentifierExpected,
+                    ));
+                }
+                imported
+            }
+        };
+        self.parse_import_specifier_local(imported, type_only, start)
+    }
+
+    /// Parse the TypeScript `type` modifier of an import or export specifier.
+    ///
+    /// In `{ type as x }`, `type` is not a modifier but the imported or
+    /// exported name, which is returned instead.
+    fn parse_specifier_type_modifier(&mut self) -> Result<(bool, Option<Identifier>), ParseError> {
+        if !self.peek_contextual_keyword(ContextualKeyword::Type) {
+            return Ok((false, None));
+        }
+        self.next_token();
+        let type_name = self.identifier();
+        let is_modifier = match self.peek_token() {
+            Some(Token::IdentifierName) => !self.peek_contextual_keyword(ContextualKeyword::As),
+            Some(token) => token == Token::StringLiteral || token.is_keyword(),
+            None => false,
+        };
+        if is_modifier {
+            self.expect_typescript("Type modifiers on import and export names")?;
+            Ok((true, None))
+        } else {
+            Ok((false, Some(type_name)))
+        }
+    }
+
+    /// Parse a module export name, which is an identifier name or a string
+    /// literal.
+    fn parse_module_export_name(&mut self) -> Result<Identifier, ParseError> {
         match self.next_token() {
-            Some(actual) if actual == expected => Ok(()),
-            actual => Err(ParseError {
-                reason: format!("Expected token `{:?}`, but was `{:?}`", expected, actual),
+            Some(Token::StringLiteral) => Ok(Identifier {
+                value: self.value.clone(),
+                span: self.span,
             }),
+            Some(token) if token == Token::IdentifierName || token.is_keyword() => {
+                Ok(self.identifier())
+            }
+            found => Err(self.error(found, ParseErrorKind::IdentifierExpected)),
         }
     }
 
-    fn next_token(&mut self) -> Option<Token> {
-        if let Some(token) = self.lookahead.take() {
-            return Some(token);
-        }
-        self.tokenizer.next_token()
+    /// Parse the optional `as` clause of an import specifier, whose imported
+    /// name has already been parsed.
+    fn parse_import_specifier_local(
+        &mut self,
+        imported: Identifier,
+        type_only: bool,
+        start: Position,
+    ) -> Result<ImportSpecifier, ParseError> {
+        let local = if self.peek_contextual_keyword(ContextualKeyword::As) {
+            self.next_token();
+            match self.parser_binding_identifier()? {
+                Some(local) => local,
+                None => return Err(self.expected(ParseErrorKind::IdentifierExpected)),
+            }
+        } else {
+            // Without `as`, the imported name is also the local binding.
+            self.check_identifier(&imported)?;
+            imported.clone()
+        };
+        Ok(ImportSpecifier {
+            imported,
+            local,
+            type_only,
+            span: self.span_from(start),
+        })
     }
+
+    /// Parse the import attributes of an import declaration, for example,
+    /// `with { type: "json" }`.
+    fn parse_with_clause(&mut self) -> Result<Vec<ImportAttribute>, ParseError> {
+        let mut attributes = vec![];
+        if self.peek_token() != Some(Token::WithKeyword) {
+            return Ok(attributes);
+        }
+        self.next_token();
+        self.expect_ecma_version("Import attributes", EcmaVersion::Es2025)?;
+        self.expect_token(Token::LeftBrace)?;
+        loop {
+            if let Some(Token::RightBrace) = self.peek_token() {
+                self.next_token();
+                break;
+            }
+            let key = self.parse_module_export_name()?;
+            let start = key.span.start;
+            self.expect_token(Token::Colon)?;
+            let value = match self.next_token() {
+                Some(Token::StringLiteral) => self.string_literal(),
+                found => return Err(self.unexpected_token(found, vec![Token::StringLiteral])),
+            };
+            attributes.push(ImportAttribute {
+                key,
+                value,
+                span: self.span_from(start),
+            });
+            match self.next_token() {
+                Some(Token::Comma) => continue,
+                Some(Token::RightBrace) => break,
+                found => {
+                    return Err(self.unexpected_token(found, vec![Token::Comma, Token::RightBrace]))
+                }
+            }
+        }
+        Ok(attributes)
+    }
+
+    // Parse an export declaration.
+    fn parse_export_declaration(&mut self) -> Result<ExportDeclaration, ParseError> {
+        let start = self.span.start;
+        // TypeScript type-only exports. Type alias declarations, such as
+        // `export type T = U`, are not supported.
+        let type_only = self.peek_contextual_keyword(ContextualKeyword::Type);
+        if type_only {
+            self.next_token();
+            self.expect_typescript("`export type` declarations")?;
+            if !matches!(
+                self.peek_token(),
+                Some(Token::Asterisk) | Some(Token::LeftBrace)
+            ) {
+                let found = self.next_token();
+                return Err(self.unexpected_token(found, vec![Token::Asterisk, Token::LeftBrace]));
+            }
+        }
+        match self.peek_token() {
+            Some(Token::Asterisk) => self.parse_export_all_declaration(start, type_only),
+            Some(Token::LeftBrace) => self.parse_named_exports(start, type_only),
+            Some(Token::Assignment) => {
+                // TypeScript `export = expression`.
+                self.next_token();
+                self.expect_typescript("`export =` assignments")?;
+                let expression = match self.parse_assignment_expression()? {
+                    Some(expression) => expression,
+                    None => self.expression_expected()?,
+                };
+                self.parse_semicolon()?;
+                Ok(ExportDeclaration::Assignment(ExportAssignment {
+                    expression,
+                    span: self.span_from(start),
+                }))
+            }
+            Some(Token::ConstKeyword) | Some(Token::LetKeyword) | Some(Token::VarKeyword) => {
+                self.next_token();
+                let declaration = Statement::VariableStatement(self.parse_variable_statement()?);
+                Ok(ExportDeclaration::Declaration(ExportedDeclaration {
+                    declaration,
+                    span: self.span_from(start),
+                }))
+            }
+            Some(Token::ClassKeyword) => {
+                let declaration = Statement::ClassDeclaration(self.parse_class(true)?);
+                Ok(ExportDeclaration::Declaration(ExportedDeclaration {
+                    declaration,
+                    span: self.span_from(start),
+                }))
+            }
+            _ if self.peek_function_declaration() => {
+                let declaration =
+                    Statement::FunctionDeclaration(self.parse_function_declaration(true)?);
+                Ok(ExportDeclaration::Declaration(ExportedDeclaration {
+                    declaration,
+                    span: self.span_from(start),
+                }))
+            }
+            Some(Token::DefaultKeyword) => {
+                self.next_token();
+                // The name of the function or class is optional here.
+                let declaration = if self.peek_function_declaration() {
+                    DefaultDeclaration::FunctionDeclaration(self.parse_function_declaration(false)?)
+                } else if self.peek_token() == Some(Token::ClassKeyword) {
+                    DefaultDeclaration::ClassDeclaration(self.parse_class(false)?)
+                } else {
+                    let expression = match self.parse_assignment_expression()? {
+                        Some(expression) => expression,
+                        None => self.expression_expected()?,
+                    };
+                    self.parse_semicolon()?;
+                    DefaultDeclaration::Expression(expression)
+                };
+                Ok(ExportDeclaration::Default(ExportDefaultDeclaration {
+                    declaration,
+                    span: self.span_from(start),
+                }))
+            }
+            _ => Err(self.expected(ParseErrorKind::DeclarationOrStatementExpected)),
+        }
+    }
+
+    /// Parse an `export * from "m"` or `export * as ns from "m"` declaration.
+    fn parse_export_all_declaration(
+        &mut self,
+        start: Position,
+        type_only: bool,
+    ) -> Result<ExportDeclaration, ParseError> {
+        self.expect_token(Token::Asterisk)?;
+        let exported = if self.peek_contextual_keyword(ContextualKeyword::As) {
+            self.next_token();
+            Some(self.parse_module_export_name()?)
+        } else {
+            None
+        };
+        let module_specifier = match self.parse_from_clause()? {
+            Some(module_specifier) => module_specifier,
+            None => {
+                let found = self.next_token();
+                return Err(self.unexpected_token(found, vec![Token::StringLiteral]));
+            }
+        };
+        self.parse_semicolon()?;
+        Ok(ExportDeclaration::All(ExportAllDeclaration {
+            exported,
+            module_specifier,
+            type_only,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Parse an `export { a as b }` declaration, which can also re-export
+    /// from another module.
+    fn parse_named_exports(
+        &mut self,
+        start: Position,
+        type_only: bool,
+    ) -> Result<ExportDeclaration, ParseError> {
+        self.expect_token(Token::LeftBrace)?;
+        let mut specifiers = vec![];
+        // The first local name that is not a reference to a local binding,
+        // which is allowed only when re-exporting from another module.
+        let mut local_error = None;
+        loop {
+            if let Some(Token::RightBrace) = self.peek_token() {
+                self.next_token();
+                break;
+            }
+            let (specifier, error) = self.parse_export_specifier()?;
+            specifiers.push(specifier);
+            local_error = local_error.or(error);
+            match self.next_token() {
+                Some(Token::Comma) => continue,
+                Some(Token::RightBrace) => break,
+                found => {
+                    return Err(self.unexpected_token(found, vec![Token::Comma, Token::RightBrace]))
+                }
+            }
+        }
+        let module_specifier = if self.peek_contextual_keyword(ContextualKeyword::From) {
+            match self.parse_from_clause()? {
+                Some(module_specifier) => Some(module_specifier),
+                None => {
+                    let found = self.next_token();
+                    return Err(self.unexpected_token(found, vec![Token::StringLiteral]));
+                }
+            }
+        } else {
+            None
+        };
+        if let (None, Some(error)) = (&module_specifier, local_error) {
+            return Err(error);
+        }
+        self.parse_semicolon()?;
+        Ok(ExportDeclaration::Named(NamedExports {
+            specifiers,
+            module_specifier,
+            type_only,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Parse an export specifier.
+    ///
+    /// Also returns the error to report if the local name turns out to be a
+    /// reference to a local binding, because there is no `from` clause, but
+    /// is a string literal or a reserved word.
+    fn parse_export_specifier(
+        &mut self,
+    ) -> Result<(ExportSpecifier, Option<ParseError>), ParseError> {
+        let start = self.peek_span().start;
+        let (type_only, type_name) = self.parse_specifier_type_modifier()?;
+        let is_string = type_name.is_none() && self.peek_token() == Some(Token::StringLiteral);
+        let local = match type_name {
+            Some(type_name) => type_name,
+            None => self.parse_module_export_name()?,
+        };
+        let local_error = if is_string {
+            Some(ParseError::new(
+                ParseErrorKind::IdentifierExpected,
+                local.span,
+            ))
+        } else {
+            self.check_identifier(&local).err()
+        };
+        let exported = if self.peek_contextual_keyword(ContextualKeyword::As) {
+            self.next_token();
+            self.parse_module_export_name()?
+        } else {
+            local.clone()
+        };
+        let specifier = ExportSpecifier {
+            local,
+            exported,
+            type_only,
+            span: self.span_from(start),
+        };
+        Ok((specifier, local_error))
+    }
+
+    fn parse_from_clause(&mut self) -> Result<Option<ModuleSpecifier>, ParseError> {
+        if !self.peek_contextual_keyword(ContextualKeyword::From) {
+            return Err(self.expected(ParseErrorKind::MissingFrom));
+        }
+        self.next_token();
+        self.parse_module_specifier()
+    }
+
+    fn parse_module_specifier(&mut self) -> Result<Option<ModuleSpecifier>, ParseError> {
+        if let Some(Token::StringLiteral) = self.peek_token() {
+            self.expect_token(Token::StringLiteral)?;
+            self.check_string_literal()?;
+            Ok(Some(ModuleSpecifier {
+                value: self.value.clone(),
+                span: self.span,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Parse a variable statement, which is a variable declaration that
+    /// ends with an optional `;`.
+    fn parse_variable_statement(&mut self) -> Result<VariableStatement, ParseError> {
+        let mut stmt = self.parse_variable_declaration()?;
+        check_initializers(&stmt)?;
+        self.parse_semicolon()?;
+        stmt.span = self.span_from(stmt.span.start);
+        Ok(stmt)
+    }
+
+    /// Parse a variable declaration after the `var`, `let`, `const` or
+    /// `using` keyword.
+    ///
+    /// Missing initializers are not reported here because the declaration
+    /// in the head of a `for`-`in` or `for`-`of` statement has none.
+    fn parse_variable_declaration(&mut self) -> Result<VariableStatement, ParseError> {
+        let start = self.span.start;
+        let kind = match self.slice.as_str() {
+            "const" => VariableKind::Const,
+            "let" => VariableKind::Let,
+            _ if self.keyword == Some(ContextualKeyword::Using) => VariableKind::Using,
+            _ => VariableKind::Var,
+        };
+        if kind != VariableKind::Var {
+            self.expect_ecma_version("Lexical declarations", EcmaVersion::Es2015)?;
+        }
+        let mut declarations = Vec::new();
+        loop {
+            let start = self.peek_span().start;
+            let id = match kind {
+                // A `using` declaration can only bind identifiers.
+                VariableKind::Using => match self.parser_binding_identifier()? {
+                    Some(identifier) => Pattern::Identifier(identifier),
+                    None => return Err(self.expected(ParseErrorKind::IdentifierExpected)),
+                },
+                _ => self.parse_binding_pattern()?,
+            };
+            let initializer = self.parse_initializer()?;
+            let mut span = self.span_from(start);
+            // A missing initializer is placed at the token after it, which
+            // has not been consumed.
+            if let Some(initializer) = &initializer {
+                if initializer.span().end.offset > span.end.offset {
+                    span.end = initializer.span().end;
+                }
+            }
+            declarations.push(VariableDeclarator {
+                id,
+                initializer,
+                span,
+            });
+            if self.peek_token() != Some(Token::Comma) {
+                break;
+            }
+            self.next_token();
+        }
+        Ok(VariableStatement {
+            kind,
+            declarations,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Returns true if the next tokens start a `using` declaration, which is
+    /// `using` followed by a binding identifier on the same line.
+    fn peek_using_declaration(&mut self) -> bool {
+        self.peek_contextual_keyword(ContextualKeyword::Using)
+            && matches!(
+                self.peek_second_token(),
+                Some((Token::IdentifierName, false))
+            )
+    }
+
+    /// Parse the `;` that ends a statement.
+    ///
+    /// The `;` is inserted automatically before a `}`, at the end of input,
+    /// or before a token on a new line, where the token is not allowed.
+    fn parse_semicolon(&mut self) -> Result<(), ParseError> {
+        match self.peek_token() {
+            Some(Token::Semicolon) => {
+                self.next_token();
+                Ok(())
+            }
+            None | Some(Token::RightBrace) => Ok(()),
+            _ if self.peek_line_terminator() => Ok(()),
+            found => {
+                self.next_token();
+                Err(self.unexpected_token(found, vec![Token::Semicolon]))
+            }
+        }
+    }
+
+    fn parse_optional_semicolon(&mut self) {
+        if let Some(Token::Semicolon) = self.peek_token() {
+            self.next_token();
+        }
+    }
+
+    fn parser_binding_identifier(&mut self) -> Result<Option<Identifier>, ParseError> {
+        match self.peek_token() {
+            Some(Token::IdentifierName) => {
+                self.next_token();
+                let identifier = self.identifier();
+                self.check_binding_identifier(&identifier)?;
+                Ok(Some(identifier))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns true if the next tokens start a function declaration, which
+    /// is `function` or `async function`.
+    fn peek_function_declaration(&mut self) -> bool {
+        if self.peek_token() == Some(Token::FunctionKeyword) {
+            return true;
+        }
+        if !self.peek_contextual_keyword(ContextualKeyword::Async) {
+            return false;
+        }
+        // A line terminator after `async` makes it an identifier.
+        matches!(
+            self.peek_second_token(),
+            Some((Token::FunctionKeyword, false))
+        )
+    }
+
+    /// Parse a function declaration, which starts with `function` or
+    /// `async function`.
+    fn parse_function_declaration(&mut self, name_required: bool) -> Result<Function, ParseError> {
+        let start = self.peek_span().start;
+        let is_async = self.peek_contextual_keyword(ContextualKeyword::Async);
+        if is_async {
+            self.next_token();
+            self.expect_ecma_version("Async functions", EcmaVersion::Es2017)?;
+        }
+        self.expect_token(Token::FunctionKeyword)?;
+        self.parse_function(start, is_async, name_required)
+    }
+
+    /// Parse the rest of a function declaration or expression after the
+    /// `function` keyword.
+    fn parse_function(
+        &mut self,
+        start: Position,
+        is_async: bool,
+        name_required: bool,
+    ) -> Result<Function, ParseError> {
+        let is_generator = self.parse_generator_asterisk(is_async)?;
+        let id = self.parser_binding_identifier()?;
+        if id.is_none() && name_required {
+            return Err(self.expected(ParseErrorKind::IdentifierExpected));
+        }
+        let (params, body) = self.with_function_context(false, false, |parser| {
+            let params = parser.parse_formal_parameters()?;
+            // Duplicate parameters are allowed only in sloppy mode functions
+            // with simple parameter lists.
+            if parser.is_strict() || !is_simple_parameter_list(&params) {
+                check_duplicate_parameters(&params)?;
+            }
+            let body = parser.parse_function_body(
+                &params,
+                is_async,
+                is_generator,
+                Self::parse_block_statement,
+            )?;
+            Ok((params, body))
+        })?;
+        Ok(Function {
+            id,
+            params,
+            body,
+            is_async,
+            is_generator,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse the `*` that makes a function or method a generator, and return
+    /// true if there is one.
+    fn parse_generator_asterisk(&mut self, is_async: bool) -> Result<bool, ParseError> {
+        if self.peek_token() != Some(Token::Asterisk) {
+            return Ok(false);
+        }
+        self.next_token();
+        if is_async {
+            self.expect_ecma_version("Async generators", EcmaVersion::Es2018)?;
+        } else {
+            self.expect_ecma_version("Generators", EcmaVersion::Es2015)?;
+        }
+        Ok(true)
+    }
+
+    /// Parse a class declaration or expression, which starts with `class`.
+    fn parse_class(&mut self, name_required: bool) -> Result<Class, ParseError> {
+        self.expect_token(Token::ClassKeyword)?;
+        let start = self.span.start;
+        self.expect_ecma_version("Classes", EcmaVersion::Es2015)?;
+        // All parts of a class are strict mode code.
+        let in_class = self.in_class;
+        self.in_class = true;
+        let class =
+            self.with_allow_in(true, |parser| parser.parse_class_tail(start, name_required));
+        self.in_class = in_class;
+        class
+    }
+
+    /// Parse the rest of a class after the `class` keyword.
+    fn parse_class_tail(
+        &mut self,
+        start: Position,
+        name_required: bool,
+    ) -> Result<Class, ParseError> {
+        let id = self.parser_binding_identifier()?;
+        if id.is_none() && name_required {
+            return Err(self.expected(ParseErrorKind::IdentifierExpected));
+        }
+        let super_class = if self.peek_token() == Some(Token::ExtendsKeyword) {
+            self.next_token();
+            match self.parse_cover(Self::parse_left_hand_side_expression)? {
+                (_, Some(error)) => return Err(error),
+                (Some(super_class), None) => Some(super_class),
+                (None, None) => Some(self.expression_expected()?),
+            }
+        } else {
+            None
+        };
+        self.expect_token(Token::LeftBrace)?;
+        let mut body = Vec::new();
+        let mut has_constructor = false;
+        loop {
+            match self.peek_token() {
+                Some(Token::RightBrace) => {
+                    self.next_token();
+                    break;
+                }
+                Some(Token::Semicolon) => {
+                    self.next_token();
+                }
+                None => return Err(self.unexpected_token(None, vec![Token::RightBrace])),
+                Some(_) => {
+                    let leading = self.leading_comments();
+                    let element = self.parse_class_element(super_class.is_some())?;
+                    self.attach_comments(leading, element.span());
+                    if let ClassElement::MethodDefinition(MethodDefinition {
+                        kind: MethodKind::Constructor,
+                        key,
+                        ..
+                    }) = &element
+                    {
+                        if has_constructor {
+                            return Err(ParseError::new(
+                                ParseErrorKind::DuplicateConstructor,
+                                key.span(),
+                            ));
+                        }
+                        has_constructor = true;
+                    }
+                    body.push(element);
+                }
+            }
+        }
+        Ok(Class {
+            id,
+            super_class,
+            body,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse a class element, which is a method, a field or a static block.
+    ///
+    /// `derived` is set for elements of classes with an `extends` clause.
+    fn parse_class_element(&mut self, derived: bool) -> Result<ClassElement, ParseError> {
+        let start = self.peek_span().start;
+        let is_static = self.parse_modifier(ContextualKeyword::Static, false);
+        if is_static && self.peek_token() == Some(Token::LeftBrace) {
+            self.expect_ecma_version("Class static blocks", EcmaVersion::Es2022)?;
+            // `return` statements are not allowed in static blocks, but
+            // `new.target` and `super` property accesses are.
+            let context = StatementContext {
+                allow_new_target: true,
+                allow_super_property: true,
+                ..StatementContext::default()
+            };
+            let block = self.with_context(context, Self::parse_block_statement)?;
+            return Ok(ClassElement::StaticBlock(BlockStatement {
+                stmts: block.stmts,
+                span: self.span_from(start),
+            }));
+        }
+        let is_accessor = self.parse_modifier(ContextualKeyword::Accessor, true);
+        if is_accessor {
+            self.expect_typescript("Auto-accessors")?;
+        }
+        let is_async = !is_accessor && self.parse_modifier(ContextualKeyword::Async, true);
+        if is_async {
+            self.expect_ecma_version("Async functions", EcmaVersion::Es2017)?;
+        }
+        let is_generator = !is_accessor && self.parse_generator_asterisk(is_async)?;
+        let mut kind = MethodKind::Method;
+        if !is_accessor && !is_async && !is_generator {
+            if self.parse_modifier(ContextualKeyword::Get, false) {
+                kind = MethodKind::Get;
+            } else if self.parse_modifier(ContextualKeyword::Set, false) {
+                kind = MethodKind::Set;
+            }
+        }
+        let key = self.parse_class_element_key()?;
+        if is_static && is_prototype_key(&key) {
+            return Err(ParseError::new(ParseErrorKind::StaticPrototype, key.span()));
+        }
+        let is_constructor = !is_static && is_constructor_key(&key);
+        if self.peek_token() == Some(Token::LeftParenthesis) && !is_accessor {
+            if is_constructor {
+                if kind != MethodKind::Method || is_async || is_generator {
+                    return Err(ParseError::new(
+                        ParseErrorKind::InvalidConstructor,
+                        key.span(),
+                    ));
+                }
+                kind = MethodKind::Constructor;
+            }
+            let value = self.parse_method_function(&kind, is_async, is_generator, derived)?;
+            return Ok(ClassElement::MethodDefinition(MethodDefinition {
+                key,
+                kind,
+                value,
+                is_static,
+                span: self.span_from(start),
+            }));
+        }
+        if kind != MethodKind::Method || is_async || is_generator {
+            let found = self.next_token();
+            return Err(self.unexpected_token(found, vec![Token::LeftParenthesis]));
+        }
+        if is_constructor_key(&key) {
+            return Err(ParseError::new(
+                ParseErrorKind::InvalidConstructor,
+                key.span(),
+            ));
+        }
+        self.expect_ecma_version("Class fields", EcmaVersion::Es2022)?;
+        let value = self.with_function_context(true, false, Self::parse_initializer)?;
+        // A field ends with a `;`, which can be omitted before a `}` or a
+        // line terminator.
+        match self.peek_token() {
+            Some(Token::Semicolon) => {
+                self.next_token();
+            }
+            Some(Token::RightBrace) => {}
+            _ if self.peek_line_terminator() => {}
+            _ => {
+                let found = self.next_token();
+                return Err(self.unexpected_token(found, vec![Token::Semicolon]));
+            }
+        }
+        let property = PropertyDefinition {
+            key,
+            value,
+            is_static,
+            span: self.span_from(start),
+        };
+        if is_accessor {
+            Ok(ClassElement::AccessorProperty(property))
+        } else {
+            Ok(ClassElement::PropertyDefinition(property))
+        }
+    }
+
+    /// Parse the parameter list and the body of a method of `kind`.
+    ///
+    /// A getter takes no parameters, and a setter takes exactly one, which
+    /// is not a rest parameter. `derived` is set for methods of classes with
+    /// an `extends` clause, whose constructors can call `super`.
+    fn parse_method_function(
+        &mut self,
+        kind: &MethodKind,
+        is_async: bool,
+        is_generator: bool,
+        derived: bool,
+    ) -> Result<Function, ParseError> {
+        let start = self.peek_span().start;
+        let super_call = derived && *kind == MethodKind::Constructor;
+        let (params, body) = self.with_function_context(true, super_call, |parser| {
+            let params = parser.parse_formal_parameters()?;
+            parser.check_method_parameters(kind, &params, start)?;
+            let body = parser.parse_function_body(
+                &params,
+                is_async,
+                is_generator,
+                Self::parse_block_statement,
+            )?;
+            Ok((params, body))
+        })?;
+        Ok(Function {
+            id: None,
+            params,
+            body,
+            is_async,
+            is_generator,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Checks that `params` of a method of `kind`, which start at `start`,
+    /// have no duplicates, and that accessors have the right number of them.
+    fn check_method_parameters(
+        &self,
+        kind: &MethodKind,
+        params: &[Pattern],
+        start: Position,
+    ) -> Result<(), ParseError> {
+        let error = match (kind, params) {
+            (MethodKind::Get, [_, ..]) => Some(ParseErrorKind::GetterParameters),
+            (MethodKind::Set, [Pattern::RestElement(_)]) => Some(ParseErrorKind::SetterParameters),
+            (MethodKind::Set, [_]) => None,
+            (MethodKind::Set, _) => Some(ParseErrorKind::SetterParameters),
+            _ => None,
+        };
+        if let Some(kind) = error {
+            return Err(ParseError::new(kind, self.span_from(start)));
+        }
+        check_duplicate_parameters(params)
+    }
+
+    /// Consumes the contextual keyword `keyword` and returns true if it is a
+    /// modifier of a class element or an object literal property, rather
+    /// than the name of the element.
+    ///
+    /// If `same_line` is set, the modifier must be on the same line as the
+    /// token after it.
+    fn parse_modifier(&mut self, keyword: ContextualKeyword, same_line: bool) -> bool {
+        if !self.peek_contextual_keyword(keyword) {
+            return false;
+        }
+        match self.peek_second_token() {
+            None
+            | Some((
+                Token::Assignment
+                | Token::Colon
+                | Token::Comma
+                | Token::LeftParenthesis
+                | Token::RightBrace
+                | Token::Semicolon,
+                _,
+            )) => false,
+            Some((_, true)) if same_line => false,
+            Some(_) => {
+                self.next_token();
+                true
+            }
+        }
+    }
+
+    /// Parse the key of a class element, which is a property key or a
+    /// private name.
+    fn parse_class_element_key(&mut self) -> Result<PropertyKey, ParseError> {
+        if self.peek_token() != Some(Token::PrivateIdentifier) {
+            return self.parse_property_key();
+        }
+        self.next_token();
+        self.expect_ecma_version("Private class members", EcmaVersion::Es2022)?;
+        if self.value == "constructor" {
+            return Err(ParseError::new(
+                ParseErrorKind::PrivateConstructor,
+                self.span,
+            ));
+        }
+        Ok(PropertyKey::PrivateIdentifier(Identifier {
+            value: self.value.clone(),
+            span: self.span,
+        }))
+    }
+
+    /// Parse a parenthesized parameter list.
+    fn parse_formal_parameters(&mut self) -> Result<Vec<Pattern>, ParseError> {
+        self.expect_token(Token::LeftParenthesis)?;
+        let mut params = Vec::new();
+        let mut trailing_comma = false;
+        loop {
+            if self.peek_token() == Some(Token::RightParenthesis) {
+                self.next_token();
+                break;
+            }
+            params.push(self.parse_binding_element()?);
+            trailing_comma = false;
+            match self.next_token() {
+                Some(Token::Comma) => trailing_comma = true,
+                Some(Token::RightParenthesis) => break,
+                found => {
+                    return Err(
+                        self.unexpected_token(found, vec![Token::Comma, Token::RightParenthesis])
+                    )
+                }
+            }
+        }
+        self.check_parameters(&params, trailing_comma)?;
+        Ok(params)
+    }
+
+    /// Checks that a rest parameter in `params` is the last parameter, and
+    /// that a trailing comma after the last parameter is allowed.
+    fn check_parameters(&self, params: &[Pattern], trailing_comma: bool) -> Result<(), ParseError> {
+        let rest = params
+            .iter()
+            .position(|param| matches!(param, Pattern::RestElement(_)));
+        if let Some(index) = rest {
+            if index + 1 < params.len() || trailing_comma {
+                return Err(ParseError::new(
+                    ParseErrorKind::RestElementNotLast,
+                    params[index].span(),
+                ));
+            }
+        }
+        if trailing_comma {
+            self.expect_ecma_version("Trailing commas in parameter lists", EcmaVersion::Es2017)?;
+        }
+        Ok(())
+    }
+
+    /// Parse a binding element, which is a binding pattern with an optional
+    /// default value, or a rest element.
+    fn parse_binding_element(&mut self) -> Result<Pattern, ParseError> {
+        if self.peek_token() == Some(Token::Spread) {
+            self.next_token();
+            let start = self.span.start;
+            self.expect_ecma_version("Rest elements", EcmaVersion::Es2015)?;
+            let argument = self.parse_binding_pattern()?;
+            return Ok(Pattern::RestElement(Box::new(RestElement {
+                argument,
+                span: self.span_from(start),
+            })));
+        }
+        let pattern = self.parse_binding_pattern()?;
+        self.parse_default_value(pattern)
+    }
+
+    /// Parse the default value of `pattern`, if there is one.
+    fn parse_default_value(&mut self, pattern: Pattern) -> Result<Pattern, ParseError> {
+        if self.peek_token() != Some(Token::Assignment) {
+            return Ok(pattern);
+        }
+        self.next_token();
+        self.expect_ecma_version("Default values", EcmaVersion::Es2015)?;
+        // A pattern with a default value is always inside brackets, where
+        // `in` is allowed.
+        let right = self.with_allow_in(true, Self::parse_expression)?;
+        let span = Span::new(pattern.span().start, right.span().end);
+        Ok(Pattern::AssignmentPattern(Box::new(AssignmentPattern {
+            left: pattern,
+            right,
+            span,
+        })))
+    }
+
+    /// Parse a binding identifier or a destructuring pattern.
+    fn parse_binding_pattern(&mut self) -> Result<Pattern, ParseError> {
+        match self.peek_token() {
+            Some(Token::LeftSquareBracket) => self.parse_array_pattern().map(Pattern::ArrayPattern),
+            Some(Token::LeftBrace) => self.parse_object_pattern().map(Pattern::ObjectPattern),
+            _ => match self.parser_binding_identifier()? {
+                Some(identifier) => Ok(Pattern::Identifier(identifier)),
+                None => Err(self.expected(ParseErrorKind::IdentifierExpected)),
+            },
+        }
+    }
+
+    fn parse_array_pattern(&mut self) -> Result<ArrayPattern, ParseError> {
+        self.expect_token(Token::LeftSquareBracket)?;
+        let start = self.span.start;
+        self.expect_ecma_version("Destructuring patterns", EcmaVersion::Es2015)?;
+        let mut elements = Vec::new();
+        loop {
+            match self.peek_token() {
+                Some(Token::RightSquareBracket) => {
+                    self.next_token();
+                    break;
+                }
+                Some(Token::Comma) => {
+                    // A hole.
+                    self.next_token();
+                    elements.push(None);
+                    continue;
+                }
+                _ => {}
+            }
+            let element = self.parse_binding_element()?;
+            let rest_span = match &element {
+                Pattern::RestElement(rest) => Some(rest.span),
+                _ => None,
+            };
+            elements.push(Some(element));
+            match self.next_token() {
+                Some(Token::Comma) => {
+                    if let Some(span) = rest_span {
+                        return Err(ParseError::new(ParseErrorKind::RestElementNotLast, span));
+                    }
+                }
+                Some(Token::RightSquareBracket) => break,
+                found => {
+                    return Err(
+                        self.unexpected_token(found, vec![Token::Comma, Token::RightSquareBracket])
+                    )
+                }
+            }
+        }
+        Ok(ArrayPattern {
+            elements,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_object_pattern(&mut self) -> Result<ObjectPattern, ParseError> {
+        self.expect_token(Token::LeftBrace)?;
+        let start = self.span.start;
+        self.expect_ecma_version("Destructuring patterns", EcmaVersion::Es2015)?;
+        let mut properties = Vec::new();
+        loop {
+            if self.peek_token() == Some(Token::RightBrace) {
+                self.next_token();
+                break;
+            }
+            let property = self.parse_object_pattern_property()?;
+            let rest_span = match &property {
+                ObjectPatternProperty::RestElement(rest) => Some(rest.span),
+                ObjectPatternProperty::Property(_) => None,
+            };
+            properties.push(property);
+            match self.next_token() {
+                Some(Token::Comma) => {
+                    if let Some(span) = rest_span {
+                        return Err(ParseError::new(ParseErrorKind::RestElementNotLast, span));
+                    }
+                }
+                Some(Token::RightBrace) => break,
+                found => {
+                    return Err(self.unexpected_token(found, vec![Token::Comma, Token::RightBrace]))
+                }
+            }
+        }
+        Ok(ObjectPattern {
+            properties,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_object_pattern_property(&mut self) -> Result<ObjectPatternProperty, ParseError> {
+        if self.peek_token() == Some(Token::Spread) {
+            self.next_token();
+            let start = self.span.start;
+            self.expect_ecma_version("Object rest properties", EcmaVersion::Es2018)?;
+            // The rest property of an object pattern can only bind an
+            // identifier.
+            let argument = match self.parser_binding_identifier()? {
+                Some(identifier) => Pattern::Identifier(identifier),
+                None => return Err(self.expected(ParseErrorKind::IdentifierExpected)),
+            };
+            return Ok(ObjectPatternProperty::RestElement(RestElement {
+                argument,
+                span: self.span_from(start),
+            }));
+        }
+        let start = self.peek_span().start;
+        // Only an identifier, and not a reserved word, can be a shorthand
+        // property.
+        let identifier = self.peek_token() == Some(Token::IdentifierName);
+        let key = self.parse_property_key()?;
+        let (value, shorthand) = match key {
+            _ if self.peek_token() == Some(Token::Colon) => {
+                self.next_token();
+                let pattern = self.parse_binding_pattern()?;
+                (self.parse_default_value(pattern)?, false)
+            }
+            PropertyKey::Identifier(ref key) if identifier => {
+                let pattern = Pattern::Identifier(key.clone());
+                (self.parse_default_value(pattern)?, true)
+            }
+            _ => {
+                let found = self.next_token();
+                return Err(self.unexpected_token(found, vec![Token::Colon]));
+            }
+        };
+        Ok(ObjectPatternProperty::Property(PatternProperty {
+            key,
+            value,
+            shorthand,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Parse a property key, which is an identifier name, a string or numeric
+    /// literal, or a computed key.
+    fn parse_property_key(&mut self) -> Result<PropertyKey, ParseError> {
+        match self.next_token() {
+            Some(Token::IdentifierName) => Ok(PropertyKey::Identifier(self.identifier())),
+            Some(token) if token.is_keyword() => Ok(PropertyKey::Identifier(self.identifier())),
+            Some(Token::StringLiteral) => {
+                self.check_string_literal()?;
+                Ok(PropertyKey::StringLiteral(self.string_literal()))
+            }
+            Some(Token::NumericLiteral) => {
+                self.check_numeric_literal()?;
+                Ok(PropertyKey::NumericLiteral(self.numeric_literal()))
+            }
+            Some(Token::LeftSquareBracket) => {
+                self.expect_ecma_version("Computed property names", EcmaVersion::Es2015)?;
+                let expression = self.with_allow_in(true, Self::parse_expression)?;
+                self.expect_token(Token::RightSquareBracket)?;
+                Ok(PropertyKey::Computed(expression))
+            }
+            found => Err(self.error(found, ParseErrorKind::IdentifierExpected)),
+        }
+    }
+
+    /// Returns the last consumed token as a numeric literal.
+    fn numeric_literal(&self) -> NumericLiteral {
+        NumericLiteral {
+            value: numeric_value(&self.value),
+            raw: self.slice.clone(),
+            span: self.span,
+        }
+    }
+
+    /// Returns the last consumed token as a string literal.
+    fn string_literal(&self) -> StringLiteral {
+        StringLiteral {
+            value: self.value.clone(),
+            raw: self.slice.clone(),
+            span: self.span,
+        }
+    }
+
+    /// Returns the last consumed token as an identifier.
+    fn identifier(&self) -> Identifier {
+        Identifier {
+            value: self.value.clone(),
+            span: self.span,
+        }
+    }
+
+    fn parse_initializer(&mut self) -> Result<Option<Expression>, ParseError> {
+        match self.peek_token() {
+            Some(Token::Assignment) => {
+                self.next_token();
+                let expr = match self.parse_assignment_expression()? {
+                    Some(expr) => expr,
+                    None => self.expression_expected()?,
+                };
+                Ok(Some(expr))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn parse_assignment_expression(&mut self) -> Result<Option<Expression>, ParseError> {
+        match self.parse_cover(Self::parse_cover_assignment_expression)? {
+            (_, Some(error)) => Err(error),
+            (expression, None) => Ok(expression),
+        }
+    }
+
+    /// Parse an assignment expression that may be reinterpreted as a
+    /// pattern, such as an element of an array literal.
+    ///
+    /// Errors that apply only if the expression is not a pattern are left
+    /// in `pattern_error` for the enclosing expression to report.
+    fn parse_cover_expression(&mut self) -> Result<Expression, ParseError> {
+        match self.parse_cover_assignment_expression()? {
+            Some(expression) => Ok(expression),
+            None => self.expression_expected(),
+        }
+    }
+
+    /// Parse an assignment expression, leaving errors that apply only if the
+    /// expression is not a pattern in `pattern_error`.
+    ///
+    /// The left-hand side is parsed as a conditional expression, and
+    /// reinterpreted as an assignment target if an assignment operator
+    /// follows it.
+    fn parse_cover_assignment_expression(&mut self) -> Result<Option<Expression>, ParseError> {
+        if self.context.in_generator && self.peek_contextual_keyword(ContextualKeyword::Yield) {
+            return self.parse_yield_expression().map(Some);
+        }
+        let (left, pattern_error) = self.parse_cover(Self::parse_conditional_expression)?;
+        let left = match left {
+            Some(left) => left,
+            None => return Ok(None),
+        };
+        let op = match self
+            .peek_token()
+            .and_then(|token| assignment_operator(&token))
+        {
+            Some(op) => op,
+            None => {
+                // The expression may still be reinterpreted as a pattern by
+                // an enclosing expression, as in `[{ a = 1 }] = b`.
+                if self.pattern_error.is_none() {
+                    self.pattern_error = pattern_error;
+                }
+                return Ok(Some(left));
+            }
+        };
+        self.next_token();
+        match op {
+            AssignmentOp::ExponentiationAssignment => {
+                self.expect_ecma_version("Exponentiation expressions", EcmaVersion::Es2016)?;
+            }
+            AssignmentOp::LogicalAndAssignment
+            | AssignmentOp::LogicalNullishAssignment
+            | AssignmentOp::LogicalOrAssignment => {
+                self.expect_ecma_version("Logical assignment operators", EcmaVersion::Es2021)?;
+            }
+            _ => {}
+        }
+        // Only `=` can assign to a destructuring pattern.
+        if !matches!(op, AssignmentOp::Assignment) && !is_simple_assignment_target(&left) {
+            return Err(ParseError::new(
+                ParseErrorKind::InvalidAssignmentTarget,
+                left.span(),
+            ));
+        }
+        let left = self.expression_to_pattern(left, false)?;
+        let right = self.parse_expression()?;
+        let span = Span::new(left.span().start, right.span().end);
+        Ok(Some(Expression::AssignmentExpression(Box::new(
+            AssignmentExpression {
+                op,
+                left,
+                right,
+                span,
+            },
+        ))))
+    }
+
+    /// Parse a `yield` expression in a generator.
+    fn parse_yield_expression(&mut self) -> Result<Expression, ParseError> {
+        self.next_token();
+        let start = self.span.start;
+        // A line terminator after `yield` ends the expression, so the
+        // `yield` has no argument.
+        let (argument, delegate) = if self.peek_line_terminator() {
+            (None, false)
+        } else if self.peek_token() == Some(Token::Asterisk) {
+            self.next_token();
+            (Some(self.parse_expression()?), true)
+        } else {
+            (self.parse_assignment_expression()?, false)
+        };
+        Ok(Expression::YieldExpression(Box::new(YieldExpression {
+            argument,
+            delegate,
+            span: self.span_from(start),
+        })))
+    }
+
+    /// Parse a conditional expression, which is a binary expression if no
+    /// `?` follows it.
+    fn parse_conditional_expression(&mut self) -> Result<Option<Expression>, ParseError> {
+        let test = match self.parse_binary_expression(0)? {
+            Some(Expression::ArrowFunctionExpression(arrow)) => {
+                return Ok(Some(Expression::ArrowFunctionExpression(arrow)))
+            }
+            Some(test) => test,
+            None => return Ok(None),
+        };
+        if self.peek_token() != Some(Token::QuestionMark) {
+            return Ok(Some(test));
+        }
+        self.next_token();
+        // `in` is allowed between `?` and `:` even where it is not allowed
+        // in the rest of the expression.
+        let consequent = self.with_allow_in(true, Self::parse_expression)?;
+        self.expect_token(Token::Colon)?;
+        let alternate = self.parse_expression()?;
+        let span = Span::new(test.span().start, alternate.span().end);
+        Ok(Some(Expression::ConditionalExpression(Box::new(
+            ConditionalExpression {
+                test,
+                consequent,
+                alternate,
+                span,
+            },
+        ))))
+    }
+
+    /// Parse with `parse`, and return the first error in the parsed
+    /// expression that applies only if the expression is not reinterpreted
+    /// as a pattern.
+    fn parse_cover<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<(T, Option<ParseError>), ParseError> {
+        let outer = self.pattern_error.take();
+        let result = parse(self);
+        let error = std::mem::replace(&mut self.pattern_error, outer);
+        Ok((result?, error))
+    }
+
+    /// Parse a binary expression using precedence climbing.
+    ///
+    /// Operators that bind less tightly than `min_precedence` are left for
+    /// the caller to consume.
+    fn parse_binary_expression(
+        &mut self,
+        min_precedence: u8,
+    ) -> Result<Option<Expression>, ParseError> {
+        let mut left = match self.parse_unary_expression()? {
+            Some(left) => left,
+            None => return Ok(None),
+        };
+        // An arrow function is an assignment expression, so it cannot be an
+        // operand of a binary operator.
+        if let Expression::ArrowFunctionExpression(_) = left {
+            if min_precedence > 0 {
+                return Err(ParseError::new(
+                    ParseErrorKind::UnexpectedToken {
+                        found: Some(Token::Arrow),
+                        expected: vec![],
+                    },
+                    left.span(),
+                ));
+            }
+            return Ok(Some(left));
+        }
+        while let Some((op, precedence)) = self.peek_token().and_then(|t| binary_operator(&t)) {
+            if precedence < min_precedence || (matches!(op, BinaryOp::In) && !self.allow_in) {
+                break;
+            }
+            self.next_token();
+            if let BinaryOp::NullishCoalescing = op {
+                self.expect_ecma_version("Nullish coalescing expressions", EcmaVersion::Es2020)?;
+            }
+            if let BinaryOp::Exponentiation = op {
+                self.expect_ecma_version("Exponentiation expressions", EcmaVersion::Es2016)?;
+                if let Expression::UnaryExpression(_) | Expression::AwaitExpression(_) = left {
+                    return Err(ParseError::new(
+                        ParseErrorKind::UnaryBeforeExponentiation,
+                        left.span(),
+                    ));
+                }
+            }
+            // Exponentiation is the only right-associative binary operator.
+            let next_precedence = match op {
+                BinaryOp::Exponentiation => precedence,
+                _ => precedence + 1,
+            };
+            let right = match self.parse_binary_expression(next_precedence)? {
+                Some(right) => right,
+                None => self.expression_expected()?,
+            };
+            let span = Span::new(left.span().start, right.span().end);
+            if mixes_nullish_coalescing(&op, &left) || mixes_nullish_coalescing(&op, &right) {
+                return Err(ParseError::new(
+                    ParseErrorKind::MixedNullishCoalescing,
+                    span,
+                ));
+            }
+            left = Expression::BinaryExpression(Box::new(BinaryExpression {
+                left,
+                op,
+                right,
+                span,
+            }));
+        }
+        Ok(Some(left))
+    }
+
+    /// Parse a unary expression, which includes `await` and update
+    /// expressions.
+    fn parse_unary_expression(&mut self) -> Result<Option<Expression>, ParseError> {
+        let op = match self.peek_token() {
+            Some(Token::DeleteKeyword) => Some(UnaryOp::Delete),
+            Some(Token::ExclamationMark) => Some(UnaryOp::LogicalNot),
+            Some(Token::Minus) => Some(UnaryOp::Minus),
+            Some(Token::Plus) => Some(UnaryOp::Plus),
+            Some(Token::Tilde) => Some(UnaryOp::BitwiseNot),
+            Some(Token::TypeofKeyword) => Some(UnaryOp::Typeof),
+            Some(Token::VoidKeyword) => Some(UnaryOp::Void),
+            _ => None,
+        };
+        if let Some(op) = op {
+            self.next_token();
+            let start = self.span.start;
+            let argument = match self.parse_unary_expression()? {
+                Some(argument) => argument,
+                None => self.expression_expected()?,
+            };
+            if let UnaryOp::Delete = op {
+                if self.is_strict() && is_identifier_reference(&argument) {
+                    return Err(ParseError::new(
+                        ParseErrorKind::DeleteIdentifierInStrictMode,
+                        argument.span(),
+                    ));
+                }
+            }
+            return Ok(Some(Expression::UnaryExpression(Box::new(
+                UnaryExpression {
+                    op,
+                    argument,
+                    span: self.span_from(start),
+                },
+            ))));
+        }
+        if let Some(token @ (Token::Increment | Token::Decrement)) = self.peek_token() {
+            self.next_token();
+            let start = self.span.start;
+            let argument = match self.parse_unary_expression()? {
+                Some(argument) => argument,
+                None => self.expression_expected()?,
+            };
+            return self
+                .update_expression(token, true, argument, start)
+                .map(Some);
+        }
+        // In scripts, `await` is an identifier outside of async functions.
+        if self.peek_contextual_keyword(ContextualKeyword::Await)
+            && (self.is_await_operator() || self.module)
+        {
+            self.next_token();
+            if !self.is_await_operator() {
+                return Err(ParseError::new(
+                    ParseErrorKind::AwaitOutsideAsync,
+                    self.span,
+                ));
+            }
+            if !self.context.in_async {
+                self.expect_ecma_version("Top-level `await` expressions", EcmaVersion::Es2022)?;
+            }
+            let start = self.span.start;
+            let argument = match self.parse_unary_expression()? {
+                Some(argument) => argument,
+                None => self.expression_expected()?,
+            };
+            return Ok(Some(Expression::AwaitExpression(Box::new(
+                AwaitExpression {
+                    argument,
+                    span: self.span_from(start),
+                },
+            ))));
+        }
+        let expression = match self.parse_left_hand_side_expression()? {
+            Some(expression) => expression,
+            None => return Ok(None),
+        };
+        // A line terminator before `++` or `--` ends the expression, so the
+        // operator applies to the next expression.
+        match self.peek_token() {
+            Some(token @ (Token::Increment | Token::Decrement)) if !self.peek_line_terminator() => {
+                self.next_token();
+                let start = expression.span().start;
+                self.update_expression(token, false, expression, start)
+                    .map(Some)
+            }
+            _ => Ok(Some(expression)),
+        }
+    }
+
+    /// Returns an update expression with the `++` or `--` operator `token`,
+    /// after checking that `argument` can be assigned to.
+    fn update_expression(
+        &self,
+        token: Token,
+        prefix: bool,
+        argument: Expression,
+        start: Position,
+    ) -> Result<Expression, ParseError> {
+        if !is_simple_assignment_target(&argument) {
+            return Err(ParseError::new(
+                ParseErrorKind::InvalidAssignmentTarget,
+                argument.span(),
+            ));
+        }
+        let op = match token {
+            Token::Increment => UpdateOp::Increment,
+            _ => UpdateOp::Decrement,
+        };
+        Ok(Expression::UpdateExpression(Box::new(UpdateExpression {
+            op,
+            prefix,
+            argument,
+            span: self.span_from(start),
+        })))
+    }
+
+    /// Parse a left-hand-side expression, which is a `new` expression, a call
+    /// expression, a property access or an optional chain.
+    fn parse_left_hand_side_expression(&mut self) -> Result<Option<Expression>, ParseError> {
+        let expression = match self.peek_token() {
+            Some(Token::NewKeyword) => self.parse_new_expression()?,
+            Some(Token::SuperKeyword) => self.parse_super(true)?,
+            Some(Token::ImportKeyword) => self.parse_import_expression(true)?,
+            _ => match self.parse_primary_expression()? {
+                Some(Expression::ArrowFunctionExpression(arrow)) => {
+                    return Ok(Some(Expression::ArrowFunctionExpression(arrow)))
+                }
+                Some(expression) => expression,
+                None => return Ok(None),
+            },
+        };
+        self.parse_member_tail(expression, true).map(Some)
+    }
+
+    /// Parse the property accesses, tagged templates, calls and optional
+    /// chains that follow `expression`.
+    ///
+    /// Calls and optional chains are parsed only if `allow_call` is set,
+    /// which it is not in the callee of a `new` expression.
+    fn parse_member_tail(
+        &mut self,
+        mut expression: Expression,
+        allow_call: bool,
+    ) -> Result<Expression, ParseError> {
+        let start = expression.span().start;
+        let mut in_chain = false;
+        loop {
+            let optional = allow_call && self.peek_token() == Some(Token::OptionalChaining);
+            if optional {
+                self.next_token();
+                self.expect_ecma_version("Optional chains", EcmaVersion::Es2020)?;
+                in_chain = true;
+            }
+            let property = match self.peek_token() {
+                Some(Token::Dot) if !optional => {
+                    self.next_token();
+                    self.parse_member_property_name()?
+                }
+                Some(Token::LeftSquareBracket) => {
+                    self.next_token();
+                    let property = self.with_allow_in(true, Self::parse_sequence_expression)?;
+                    self.expect_token(Token::RightSquareBracket)?;
+                    MemberProperty::Computed(property)
+                }
+                Some(Token::LeftParenthesis) if allow_call => {
+                    let arguments = self.parse_arguments()?;
+                    expression = Expression::CallExpression(Box::new(CallExpression {
+                        callee: expression,
+                        arguments,
+                        optional,
+                        span: self.span_from(start),
+                    }));
+                    continue;
+                }
+                Some(Token::TemplateLiteral | Token::TemplateHead) if !optional => {
+                    if in_chain {
+                        return Err(ParseError::new(
+                            ParseErrorKind::TaggedTemplateInOptionalChain,
+                            self.peek_span(),
+                        ));
+                    }
+                    let quasi = self.parse_template_literal(true)?;
+                    expression =
+                        Expression::TaggedTemplateExpression(Box::new(TaggedTemplateExpression {
+                            tag: expression,
+                            quasi,
+                            span: self.span_from(start),
+                        }));
+                    continue;
+                }
+                // `a?.b` and `a?.#b`.
+                _ if optional => self.parse_member_property_name()?,
+                _ => break,
+            };
+            expression = Expression::MemberExpression(Box::new(MemberExpression {
+                object: expression,
+                property,
+                optional,
+                span: self.span_from(start),
+            }));
+        }
+        if in_chain {
+            expression = Expression::ChainExpression(Box::new(ChainExpression {
+                expression,
+                span: self.span_from(start),
+            }));
+        }
+        Ok(expression)
+    }
+
+    /// Parse the property name that follows `.` or `?.`, which is an
+    /// identifier name or a private name.
+    fn parse_member_property_name(&mut self) -> Result<MemberProperty, ParseError> {
+        match self.next_token() {
+            Some(Token::IdentifierName) => Ok(MemberProperty::Identifier(self.identifier())),
+            Some(token) if token.is_keyword() => Ok(MemberProperty::Identifier(self.identifier())),
+            Some(Token::PrivateIdentifier) => {
+                self.expect_ecma_version("Private class members", EcmaVersion::Es2022)?;
+                if !self.in_class {
+                    return Err(ParseError::new(
+                        ParseErrorKind::PrivateIdentifierOutsideClass,
+                        self.span,
+                    ));
+                }
+                Ok(MemberProperty::PrivateIdentifier(Identifier {
+                    value: self.value.clone(),
+                    span: self.span,
+                }))
+            }
+            found => Err(self.error(found, ParseErrorKind::IdentifierExpected)),
+        }
+    }
+
+    /// Parse the arguments of a call or a `new` expression.
+    fn parse_arguments(&mut self) -> Result<Vec<ExpressionOrSpread>, ParseError> {
+        self.expect_token(Token::LeftParenthesis)?;
+        let mut arguments = Vec::new();
+        let (_, pattern_error) = self.parse_cover(|parser| {
+            parser.with_allow_in(true, |parser| loop {
+                if parser.peek_token() == Some(Token::RightParenthesis) {
+                    parser.next_token();
+                    if !arguments.is_empty() {
+                        parser.expect_ecma_version(
+                            "Trailing commas in argument lists",
+                            EcmaVersion::Es2017,
+                        )?;
+                    }
+                    return Ok(());
+                }
+                let argument = if parser.peek_token() == Some(Token::Spread) {
+                    let spread =
+                        parser.parse_spread_element("Spread arguments", EcmaVersion::Es2015)?;
+                    ExpressionOrSpread::SpreadElement(spread)
+                } else {
+                    ExpressionOrSpread::Expression(parser.parse_cover_expression()?)
+                };
+                arguments.push(argument);
+                match parser.next_token() {
+                    Some(Token::Comma) => {}
+                    Some(Token::RightParenthesis) => return Ok(()),
+                    found => {
+                        return Err(parser
+                            .unexpected_token(found, vec![Token::Comma, Token::RightParenthesis]))
+                    }
+                }
+            })
+        })?;
+        match pattern_error {
+            Some(error) => Err(error),
+            None => Ok(arguments),
+        }
+    }
+
+    /// Parse a `new` expression, or the meta property `new.target`.
+    fn parse_new_expression(&mut self) -> Result<Expression, ParseError> {
+        self.expect_token(Token::NewKeyword)?;
+        let start = self.span.start;
+        if self.peek_token() == Some(Token::Dot) {
+            let meta = self.identifier();
+            self.next_token();
+            self.expect_ecma_version("`new.target` expressions", EcmaVersion::Es2015)?;
+            let expression = self.parse_meta_property(meta, ContextualKeyword::Target)?;
+            if !self.context.allow_new_target {
+                return Err(ParseError::new(
+                    ParseErrorKind::NewTargetOutsideFunction,
+                    expression.span(),
+                ));
+            }
+            return Ok(expression);
+        }
+        let callee = match self.peek_token() {
+            Some(Token::NewKeyword) => self.parse_new_expression()?,
+            Some(Token::SuperKeyword) => self.parse_super(false)?,
+            Some(Token::ImportKeyword) => self.parse_import_expression(false)?,
+            _ => match self.parse_primary_expression()? {
+                Some(Expression::ArrowFunctionExpression(arrow)) => {
+                    return Err(ParseError::new(
+                        ParseErrorKind::UnexpectedToken {
+                            found: Some(Token::Arrow),
+                            expected: vec![],
+                        },
+                        arrow.span,
+                    ))
+                }
+                Some(callee) => callee,
+                None => self.expression_expected()?,
+            },
+        };
+        let callee = self.parse_member_tail(callee, false)?;
+        let arguments = match self.peek_token() {
+            Some(Token::LeftParenthesis) => self.parse_arguments()?,
+            // `new a?.b()` is not allowed.
+            Some(Token::OptionalChaining) => {
+                let found = self.next_token();
+                return Err(self.unexpected_token(found, vec![]));
+            }
+            _ => Vec::new(),
+        };
+        Ok(Expression::NewExpression(Box::new(NewExpression {
+            callee,
+            arguments,
+            span: self.span_from(start),
+        })))
+    }
+
+    /// Parse the property of the meta property `meta`, which must be
+    /// `name`, after the `.`.
+    fn parse_meta_property(
+        &mut self,
+        meta: Identifier,
+        name: ContextualKeyword,
+    ) -> Result<Expression, ParseError> {
+        if !self.peek_contextual_keyword(name) {
+            let found = self.next_token();
+            return Err(self.unexpected_token(found, vec![]));
+        }
+        self.next_token();
+        let property = self.identifier();
+        Ok(Expression::MetaProperty(MetaProperty {
+            span: Span::new(meta.span.start, property.span.end),
+            meta,
+            property,
+        }))
+    }
+
+    /// Parse `super`, which must be followed by a property access, or by
+    /// the arguments of a call if `allow_call` is set.
+    fn parse_super(&mut self, allow_call: bool) -> Result<Expression, ParseError> {
+        self.expect_token(Token::SuperKeyword)?;
+        let span = self.span;
+        match self.peek_token() {
+            Some(Token::Dot | Token::LeftSquareBracket) if !self.context.allow_super_property => {
+                Err(ParseError::new(
+                    ParseErrorKind::SuperPropertyOutsideMethod,
+                    span,
+                ))
+            }
+            Some(Token::LeftParenthesis) if allow_call && !self.context.allow_super_call => Err(
+                ParseError::new(ParseErrorKind::SuperCallOutsideConstructor, span),
+            ),
+            Some(Token::Dot | Token::LeftSquareBracket) => Ok(Expression::Super(span)),
+            Some(Token::LeftParenthesis) if allow_call => Ok(Expression::Super(span)),
+            _ => {
+                let found = self.next_token();
+                let mut expected = vec![Token::Dot, Token::LeftSquareBracket];
+                if allow_call {
+                    expected.push(Token::LeftParenthesis);
+                }
+                Err(self.unexpected_token(found, expected))
+            }
+        }
+    }
+
+    /// Returns true if the next tokens start a dynamic import or
+    /// `import.meta`, rather than an import declaration.
+    fn peek_import_expression(&mut self) -> bool {
+        matches!(
+            self.peek_second_token(),
+            Some((Token::LeftParenthesis | Token::Dot, _))
+        )
+    }
+
+    /// Parse `import.meta`, or a dynamic import if `allow_call` is set.
+    fn parse_import_expression(&mut self, allow_call: bool) -> Result<Expression, ParseError> {
+        self.expect_token(Token::ImportKeyword)?;
+        let start = self.span.start;
+        match self.peek_token() {
+            Some(Token::Dot) => {
+                let meta = self.identifier();
+                self.next_token();
+                self.expect_ecma_version("`import.meta` expressions", EcmaVersion::Es2020)?;
+                let expression = self.parse_meta_property(meta, ContextualKeyword::Meta)?;
+                if !self.module {
+                    return Err(ParseError::new(
+                        ParseErrorKind::ImportMetaOutsideModule,
+                        expression.span(),
+                    ));
+                }
+                Ok(expression)
+            }
+            Some(Token::LeftParenthesis) if allow_call => {
+                self.next_token();
+                self.expect_ecma_version("Dynamic imports", EcmaVersion::Es2020)?;
+                let (source, options) = self.with_allow_in(true, |parser| {
+                    let source = parser.parse_expression()?;
+                    let mut options = None;
+                    if parser.peek_token() == Some(Token::Comma) {
+                        parser.next_token();
+                        if parser.peek_token() != Some(Token::RightParenthesis) {
+                            parser.expect_ecma_version("Import attributes", EcmaVersion::Es2025)?;
+                            options = Some(parser.parse_expression()?);
+                            if parser.peek_token() == Some(Token::Comma) {
+                                parser.next_token();
+                            }
+                        }
+                    }
+                    Ok((source, options))
+                })?;
+                self.expect_token(Token::RightParenthesis)?;
+                Ok(Expression::ImportExpression(Box::new(ImportExpression {
+                    source,
+                    options,
+                    span: self.span_from(start),
+                })))
+            }
+            _ => {
+                let found = self.next_token();
+                let mut expected = vec![Token::Dot];
+                if allow_call {
+                    expected.push(Token::LeftParenthesis);
+                }
+                Err(self.unexpected_token(found, expected))
+            }
+        }
+    }
+
+    fn parse_primary_expression(&mut self) -> Result<Option<Expression>, ParseError> {
+        match self.peek_token() {
+            Some(Token::IdentifierName) => {
+                self.next_token();
+                let start = self.span.start;
+                let identifier = self.identifier();
+                if self.keyword == Some(ContextualKeyword::Async) && !self.peek_line_terminator() {
+                    match self.peek_token() {
+                        Some(Token::FunctionKeyword) => {
+                            self.expect_ecma_version("Async functions", EcmaVersion::Es2017)?;
+                            self.next_token();
+                            let function = self.parse_function(start, true, false)?;
+                            return Ok(Some(Expression::FunctionExpression(Box::new(function))));
+                        }
+                        Some(Token::IdentifierName | Token::LeftParenthesis) => {
+                            return self.parse_async_arrow_function(identifier).map(Some);
+                        }
+                        _ => {}
+                    }
+                }
+                self.check_identifier(&identifier)?;
+                if self.peek_token() == Some(Token::Arrow) {
+                    let params = vec![Pattern::Identifier(identifier)];
+                    return self.parse_arrow_function(start, params, false).map(Some);
+                }
+                Ok(Some(Expression::Identifier(identifier)))
+            }
+            Some(Token::FunctionKeyword) => {
+                self.next_token();
+                let function = self.parse_function(self.span.start, false, false)?;
+                Ok(Some(Expression::FunctionExpression(Box::new(function))))
+            }
+            Some(Token::ThisKeyword) => {
+                self.next_token();
+                Ok(Some(Expression::ThisExpression(self.span)))
+            }
+            Some(Token::NullKeyword) => {
+                self.next_token();
+                Ok(Some(Expression::NullLiteral(self.span)))
+            }
+            Some(token @ (Token::TrueKeyword | Token::FalseKeyword)) => {
+                self.next_token();
+                Ok(Some(Expression::BooleanLiteral(BooleanLiteral {
+                    value: token == Token::TrueKeyword,
+                    span: self.span,
+                })))
+            }
+            Some(Token::ClassKeyword) => Ok(Some(Expression::ClassExpression(Box::new(
+                self.parse_class(false)?,
+            )))),
+            Some(Token::NumericLiteral) => {
+                self.next_token();
+                self.check_numeric_literal()?;
+                Ok(Some(Expression::NumericLiteral(self.numeric_literal())))
+            }
+            Some(Token::BigIntLiteral) => {
+                self.next_token();
+                self.expect_ecma_version("BigInt literals", EcmaVersion::Es2020)?;
+                self.check_numeric_literal()?;
+                Ok(Some(Expression::BigIntLiteral(BigIntLiteral {
+                    digits: self.value.clone(),
+                    span: self.span,
+                })))
+            }
+            Some(Token::StringLiteral) => {
+                self.next_token();
+                self.check_string_literal()?;
+                Ok(Some(Expression::StringLiteral(self.string_literal())))
+            }
+            Some(Token::TemplateLiteral | Token::TemplateHead) => Ok(Some(
+                Expression::TemplateLiteral(self.parse_template_literal(false)?),
+            )),
+            Some(Token::LeftParenthesis) => {
+                let start = self.peek_span().start;
+                self.parse_parenthesized_expression_or_arrow_function(start, None)
+                    .map(Some)
+            }
+            Some(Token::LeftSquareBracket) => Ok(Some(Expression::ArrayExpression(
+                self.with_allow_in(true, Self::parse_array_literal)?,
+            ))),
+            Some(Token::LeftBrace) => Ok(Some(Expression::ObjectExpression(
+                self.with_allow_in(true, Self::parse_object_literal)?,
+            ))),
+            Some(Token::Slash | Token::DivisonAssignment) => {
+                // An unterminated literal is left as an error token for the
+                // caller to report.
+                if self.rescan_regular_expression() != Token::RegularExpressionLiteral {
+                    return Ok(None);
+                }
+                self.next_token();
+                Ok(Some(Expression::RegExpLiteral(self.regexp_literal()?)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Parse an array literal.
+    fn parse_array_literal(&mut self) -> Result<ArrayExpression, ParseError> {
+        self.expect_token(Token::LeftSquareBracket)?;
+        let start = self.span.start;
+        let mut elements = Vec::new();
+        loop {
+            match self.peek_token() {
+                Some(Token::RightSquareBracket) => {
+                    self.next_token();
+                    break;
+                }
+                Some(Token::Comma) => {
+                    // A hole.
+                    self.next_token();
+                    elements.push(None);
+                    continue;
+                }
+                Some(Token::Spread) => {
+                    let spread =
+                        self.parse_spread_element("Spread elements", EcmaVersion::Es2015)?;
+                    self.check_trailing_comma_after_spread(spread.span, Token::RightSquareBracket);
+                    elements.push(Some(ExpressionOrSpread::SpreadElement(spread)));
+                }
+                _ => {
+                    let expression = self.parse_cover_expression()?;
+                    elements.push(Some(ExpressionOrSpread::Expression(expression)));
+                }
+            }
+            match self.next_token() {
+                Some(Token::Comma) => {}
+                Some(Token::RightSquareBracket) => break,
+                found => {
+                    return Err(
+                        self.unexpected_token(found, vec![Token::Comma, Token::RightSquareBracket])
+                    )
+                }
+            }
+        }
+        Ok(ArrayExpression {
+            elements,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse a spread element, which is `...` followed by an expression.
+    /// Spreading is available from `required` for `feature`.
+    fn parse_spread_element(
+        &mut self,
+        feature: &'static str,
+        required: EcmaVersion,
+    ) -> Result<SpreadElement, ParseError> {
+        self.expect_token(Token::Spread)?;
+        let start = self.span.start;
+        self.expect_ecma_version(feature, required)?;
+        let argument = self.parse_cover_expression()?;
+        Ok(SpreadElement {
+            argument,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Records the spread element at `span` if it is followed by a trailing
+    /// comma before the `end` token of its literal.
+    fn check_trailing_comma_after_spread(&mut self, span: Span, end: Token) {
+        if self.peek_token() == Some(Token::Comma)
+            && matches!(self.peek_second_token(), Some((token, _)) if token == end)
+        {
+            self.spreads_before_trailing_comma.push(span);
+        }
+    }
+
+    /// Parse an object literal.
+    fn parse_object_literal(&mut self) -> Result<ObjectExpression, ParseError> {
+        self.expect_token(Token::LeftBrace)?;
+        let start = self.span.start;
+        let mut properties = Vec::new();
+        let mut has_proto = false;
+        loop {
+            if self.peek_token() == Some(Token::RightBrace) {
+                self.next_token();
+                break;
+            }
+            let leading = self.leading_comments();
+            let property = self.parse_object_property()?;
+            let span = property.span();
+            if let ObjectProperty::Property(property) = &property {
+                if !property.shorthand && is_proto_key(&property.key) {
+                    // Duplicate `__proto__` properties are allowed in a
+                    // destructuring pattern.
+                    if has_proto && self.pattern_error.is_none() {
+                        self.pattern_error = Some(ParseError::new(
+                            ParseErrorKind::DuplicateProto,
+                            property.key.span(),
+                        ));
+                    }
+                    has_proto = true;
+                }
+            }
+            if let ObjectProperty::SpreadElement(spread) = &property {
+                self.check_trailing_comma_after_spread(spread.span, Token::RightBrace);
+            }
+            properties.push(property);
+            match self.peek_token() {
+                // A comment after the comma on the line where the property
+                // ends is a trailing comment of the property.
+                Some(Token::Comma) => {
+                    self.next_token();
+                    self.attach_comments(leading, span);
+                }
+                Some(Token::RightBrace) => {
+                    self.attach_comments(leading, span);
+                    self.next_token();
+                    break;
+                }
+                _ => {
+                    let found = self.next_token();
+                    return Err(self.unexpected_token(found, vec![Token::Comma, Token::RightBrace]));
+                }
+            }
+        }
+        Ok(ObjectExpression {
+            properties,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parse a property of an object literal, which is a property with a
+    /// value, a method, a getter, a setter or a spread property.
+    fn parse_object_property(&mut self) -> Result<ObjectProperty, ParseError> {
+        if self.peek_token() == Some(Token::Spread) {
+            let spread =
+                self.parse_spread_element("Object spread properties", EcmaVersion::Es2018)?;
+            return Ok(ObjectProperty::SpreadElement(spread));
+        }
+        let start = self.peek_span().start;
+        let is_async = self.parse_modifier(ContextualKeyword::Async, true);
+        if is_async {
+            self.expect_ecma_version("Async functions", EcmaVersion::Es2017)?;
+        }
+        let is_generator = self.parse_generator_asterisk(is_async)?;
+        let mut kind = MethodKind::Method;
+        if !is_async && !is_generator {
+            if self.parse_modifier(ContextualKeyword::Get, false) {
+                kind = MethodKind::Get;
+            } else if self.parse_modifier(ContextualKeyword::Set, false) {
+                kind = MethodKind::Set;
+            }
+        }
+        // Only an identifier, and not a reserved word, can be a shorthand
+        // property.
+        let identifier = self.peek_token() == Some(Token::IdentifierName);
+        let key = self.parse_property_key()?;
+        if self.peek_token() == Some(Token::LeftParenthesis) {
+            if kind == MethodKind::Method {
+                self.expect_ecma_version("Method definitions", EcmaVersion::Es2015)?;
+            }
+            let value = self.parse_method_function(&kind, is_async, is_generator, false)?;
+            return Ok(ObjectProperty::MethodDefinition(MethodDefinition {
+                key,
+                kind,
+                value,
+                is_static: false,
+                span: self.span_from(start),
+            }));
+        }
+        if kind != MethodKind::Method || is_async || is_generator {
+            let found = self.next_token();
+            return Err(self.unexpected_token(found, vec![Token::LeftParenthesis]));
+        }
+        match key {
+            _ if self.peek_token() == Some(Token::Colon) => {
+                self.next_token();
+                let value = self.parse_cover_expression()?;
+                Ok(ObjectProperty::Property(Property {
+                    key,
+                    value,
+                    shorthand: false,
+                    span: self.span_from(start),
+                }))
+            }
+            PropertyKey::Identifier(ref name) if identifier => {
+                self.expect_ecma_version("Shorthand properties", EcmaVersion::Es2015)?;
+                self.check_identifier(name)?;
+                if self.peek_token() == Some(Token::Assignment) {
+                    let pattern = Pattern::Identifier(name.clone());
+                    let value = self.parse_default_value(pattern)?;
+                    let span = self.span_from(start);
+                    if self.pattern_error.is_none() {
+                        self.pattern_error = Some(ParseError::new(
+                            ParseErrorKind::ShorthandPropertyInitializer,
+                            span,
+                        ));
+                    }
+                    return Ok(ObjectProperty::CoverInitializedName(PatternProperty {
+                        key,
+                        value,
+                        shorthand: true,
+                        span,
+                    }));
+                }
+                let value = Expression::Identifier(name.clone());
+                Ok(ObjectProperty::Property(Property {
+                    key,
+                    value,
+                    shorthand: true,
+                    span: self.span_from(start),
+                }))
+            }
+            _ => {
+                let found = self.next_token();
+                Err(self.unexpected_token(found, vec![Token::Colon]))
+            }
+        }
+    }
+
+    /// Returns the last consumed token as a regular expression literal,
+    /// after validating its pattern and flags.
+    fn regexp_literal(&self) -> Result<RegExpLiteral, ParseError> {
+        // Flags cannot contain a `/`, so the last one closes the pattern.
+        let end = self.slice.rfind('/').unwrap_or_default();
+        let pattern = &self.slice[1..end];
+        let flags = &self.slice[end + 1..];
+        regexp::validate(pattern, flags).map_err(|error| {
+            ParseError::new(ParseErrorKind::InvalidRegularExpression(error), self.span)
+        })?;
+        Ok(RegExpLiteral {
+            pattern: pattern.to_string(),
+            flags: flags.to_string(),
+            span: self.span,
+        })
+    }
+
+    /// Parse a template literal, which is either a template without
+    /// substitutions or a template head followed by substitutions.
+    fn parse_template_literal(&mut self, tagged: bool) -> Result<TemplateLiteral, ParseError> {
+        let token = self.next_token();
+        self.expect_ecma_version("Template literals", EcmaVersion::Es2015)?;
+        let start = self.span.start;
+        let mut quasis = vec![self.template_element(tagged)?];
+        let mut expressions = Vec::new();
+        if token == Some(Token::TemplateHead) {
+            loop {
+                let expression = self.with_allow_in(true, Self::parse_sequence_expression)?;
+                expressions.push(expression);
+                match self.next_token() {
+                    Some(Token::TemplateMiddle) => quasis.push(self.template_element(tagged)?),
+                    Some(Token::TemplateTail) => {
+                        quasis.push(self.template_element(tagged)?);
+                        break;
+                    }
+                    token => return Err(self.unexpected_token(token, vec![Token::TemplateTail])),
+                }
+            }
+        }
+        Ok(TemplateLiteral {
+            quasis,
+            expressions,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Returns the last consumed template token as a template element.
+    ///
+    /// Invalid escape sequences are allowed only in tagged templates.
+    fn template_element(&self, tagged: bool) -> Result<TemplateElement, ParseError> {
+        if self.invalid_escape && !tagged {
+            return Err(ParseError::new(
+                ParseErrorKind::InvalidEscapeSequence,
+                self.span,
+            ));
+        }
+        // Strip the delimiters, which are a backtick or `}` at the start,
+        // and a backtick or `${` at the end.
+        let end = if self.slice.ends_with('`') { 1 } else { 2 };
+        let raw = &self.slice[1..self.slice.len() - end];
+        Ok(TemplateElement {
+            cooked: (!self.invalid_escape).then(|| self.value.clone()),
+            raw: raw.replace("\r\n", "\n").replace('\r', "\n"),
+            span: self.span,
+        })
+    }
+
+    /// Parse a parenthesized expression or an arrow function, which both
+    /// start with a parenthesized list.
+    ///
+    /// The elements of the list are parsed as expressions and reinterpreted
+    /// as parameters if `=>` follows the list. Otherwise, the elements are a
+    /// sequence expression. A list that cannot be an expression, such as
+    /// `()` or `(a, ...b)`, must be followed by `=>`.
+    ///
+    /// If the list follows the identifier `async_keyword`, it is the
+    /// parameter list of an async arrow function if `=>` follows it, and
+    /// the arguments of a call of a function named `async` otherwise.
+    fn parse_parenthesized_expression_or_arrow_function(
+        &mut self,
+        start: Position,
+        async_keyword: Option<Identifier>,
+    ) -> Result<Expression, ParseError> {
+        let is_async = async_keyword.is_some();
+        self.expect_token(Token::LeftParenthesis)?;
+        let outer_pattern_error = self.pattern_error.take();
+        let mut elements = Vec::new();
+        let mut trailing_comma = false;
+        loop {
+            let element = match self.peek_token() {
+                Some(Token::RightParenthesis) => {
+                    self.next_token();
+                    break;
+                }
+                Some(Token::Spread) if is_async => CoverElement::SpreadElement(
+                    self.parse_spread_element("Spread arguments", EcmaVersion::Es2015)?,
+                ),
+                Some(Token::Spread) => CoverElement::Parameter(self.parse_binding_element()?),
+                _ => CoverElement::Expression(
+                    self.with_allow_in(true, Self::parse_cover_expression)?,
+                ),
+            };
+            elements.push(element);
+            trailing_comma = false;
+            match self.next_token() {
+                Some(Token::Comma) => trailing_comma = true,
+                Some(Token::RightParenthesis) => break,
+                found => {
+                    return Err(
+                        self.unexpected_token(found, vec![Token::Comma, Token::RightParenthesis])
+                    )
+                }
+            }
+        }
+        let pattern_error = std::mem::replace(&mut self.pattern_error, outer_pattern_error);
+        let is_arrow = self.peek_token() == Some(Token::Arrow);
+        match async_keyword {
+            Some(callee) if !is_arrow => {
+                if let Some(error) = pattern_error {
+                    return Err(error);
+                }
+                if trailing_comma {
+                    self.expect_ecma_version(
+                        "Trailing commas in argument lists",
+                        EcmaVersion::Es2017,
+                    )?;
+                }
+                // A rest parameter is only parsed if the list does not
+                // follow `async`.
+                let arguments = elements
+                    .into_iter()
+                    .filter_map(|element| match element {
+                        CoverElement::Expression(expression) => {
+                            Some(ExpressionOrSpread::Expression(expression))
+                        }
+                        CoverElement::SpreadElement(spread) => {
+                            Some(ExpressionOrSpread::SpreadElement(spread))
+                        }
+                        CoverElement::Parameter(_) => None,
+                    })
+                    .collect();
+                return Ok(Expression::CallExpression(Box::new(CallExpression {
+                    callee: Expression::Identifier(callee),
+                    arguments,
+                    optional: false,
+                    span: self.span_from(start),
+                })));
+            }
+            Some(_) => self.expect_ecma_version("Async functions", EcmaVersion::Es2017)?,
+            None => {}
+        }
+        let is_expression = !is_async
+            && !is_arrow
+            && !trailing_comma
+            && !elements.is_empty()
+            && elements
+                .iter()
+                .all(|element| matches!(element, CoverElement::Expression(_)));
+        if !is_expression {
+            let params = elements
+                .into_iter()
+                .map(|element| match element {
+                    CoverElement::Expression(expression) => {
+                        self.expression_to_pattern(expression, true)
+                    }
+                    CoverElement::Parameter(pattern) => Ok(pattern),
+                    CoverElement::SpreadElement(spread) => {
+                        let rest = self.spread_to_rest_element(spread, true)?;
+                        Ok(Pattern::RestElement(Box::new(rest)))
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            self.check_parameters(&params, trailing_comma)?;
+            return self.parse_arrow_function(start, params, is_async);
+        }
+        if let Some(error) = pattern_error {
+            return Err(error);
+        }
+        let mut expressions = elements
+            .into_iter()
+            .filter_map(|element| match element {
+                CoverElement::Expression(expression) => Some(expression),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let expression = if expressions.len() == 1 {
+            expressions.remove(0)
+        } else {
+            let span = Span::new(
+                expressions[0].span().start,
+                expressions[expressions.len() - 1].span().end,
+            );
+            Expression::SequenceExpression(SequenceExpression { expressions, span })
+        };
+        Ok(Expression::ParenthesizedExpression(Box::new(
+            ParenthesizedExpression {
+                expression,
+                span: self.span_from(start),
+            },
+        )))
+    }
+
+    /// Reinterprets an expression as a pattern, for example, an element of a
+    /// parenthesized list as a parameter.
+    ///
+    /// A binding pattern, such as a parameter, only binds identifiers, while
+    /// an assignment target can also assign to property accesses.
+    fn expression_to_pattern(
+        &self,
+        expression: Expression,
+        binding: bool,
+    ) -> Result<Pattern, ParseError> {
+        match expression {
+            Expression::ArrayExpression(array) => {
+                let last = array.elements.len().saturating_sub(1);
+                let elements = array
+                    .elements
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, element)| match element {
+                        None => Ok(None),
+                        Some(ExpressionOrSpread::Expression(expression)) => {
+                            self.expression_to_pattern(expression, binding).map(Some)
+                        }
+                        Some(ExpressionOrSpread::SpreadElement(spread)) => {
+                            if index != last
+                                || self.spreads_before_trailing_comma.contains(&spread.span)
+                            {
+                                return Err(ParseError::new(
+                                    ParseErrorKind::RestElementNotLast,
+                                    spread.span,
+                                ));
+                            }
+                            let rest = self.spread_to_rest_element(spread, binding)?;
+                            Ok(Some(Pattern::RestElement(Box::new(rest))))
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Pattern::ArrayPattern(ArrayPattern {
+                    elements,
+                    span: array.span,
+                }))
+            }
+            Expression::AssignmentExpression(assignment) => match assignment.op {
+                AssignmentOp::Assignment => {
+                    if binding {
+                        check_binding_pattern(&assignment.left)?;
+                    }
+                    Ok(Pattern::AssignmentPattern(Box::new(AssignmentPattern {
+                        left: assignment.left,
+                        right: assignment.right,
+                        span: assignment.span,
+                    })))
+                }
+                _ => Err(ParseError::new(
+                    ParseErrorKind::InvalidAssignmentTarget,
+                    assignment.span,
+                )),
+            },
+            Expression::Identifier(identifier) => Ok(Pattern::Identifier(identifier)),
+            Expression::MemberExpression(member) if !binding => {
+                Ok(Pattern::MemberExpression(member))
+            }
+            // A parenthesized identifier or property access can be assigned
+            // to, but a parenthesized pattern cannot, as in `([a]) = b`.
+            Expression::ParenthesizedExpression(expression)
+                if !binding && is_simple_assignment_target(&expression.expression) =>
+            {
+                self.expression_to_pattern(expression.expression, binding)
+            }
+            Expression::ObjectExpression(object) => {
+                let last = object.properties.len().saturating_sub(1);
+                let properties = object
+                    .properties
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, property)| match property {
+                        ObjectProperty::CoverInitializedName(property) => {
+                            Ok(ObjectPatternProperty::Property(property))
+                        }
+                        ObjectProperty::MethodDefinition(method) => Err(ParseError::new(
+                            ParseErrorKind::IdentifierExpected,
+                            method.span,
+                        )),
+                        ObjectProperty::Property(property) => {
+                            Ok(ObjectPatternProperty::Property(PatternProperty {
+                                key: property.key,
+                                value: self.expression_to_pattern(property.value, binding)?,
+                                shorthand: property.shorthand,
+                                span: property.span,
+                            }))
+                        }
+                        ObjectProperty::SpreadElement(spread) => {
+                            if index != last
+                                || self.spreads_before_trailing_comma.contains(&spread.span)
+                            {
+                                return Err(ParseError::new(
+                                    ParseErrorKind::RestElementNotLast,
+                                    spread.span,
+                                ));
+                            }
+                            let rest = self.spread_to_rest_element(spread, binding)?;
+                            Ok(ObjectPatternProperty::RestElement(rest))
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Pattern::ObjectPattern(ObjectPattern {
+                    properties,
+                    span: object.span,
+                }))
+            }
+            expression if binding => Err(ParseError::new(
+                ParseErrorKind::IdentifierExpected,
+                expression.span(),
+            )),
+            expression => Err(ParseError::new(
+                ParseErrorKind::InvalidAssignmentTarget,
+                expression.span(),
+            )),
+        }
+    }
+
+    /// Reinterprets a spread element as a rest element.
+    fn spread_to_rest_element(
+        &self,
+        spread: SpreadElement,
+        binding: bool,
+    ) -> Result<RestElement, ParseError> {
+        if let Expression::AssignmentExpression(assignment) = &spread.argument {
+            return Err(ParseError::new(
+                ParseErrorKind::RestElementInitializer,
+                assignment.span,
+            ));
+        }
+        Ok(RestElement {
+            argument: self.expression_to_pattern(spread.argument, binding)?,
+            span: spread.span,
+        })
+    }
+
+    /// Parse an async arrow function, or a call of a function named
+    /// `async`, after the identifier `async_keyword`.
+    fn parse_async_arrow_function(
+        &mut self,
+        async_keyword: Identifier,
+    ) -> Result<Expression, ParseError> {
+        let start = async_keyword.span.start;
+        if self.peek_token() == Some(Token::LeftParenthesis) {
+            return self
+                .parse_parenthesized_expression_or_arrow_function(start, Some(async_keyword));
+        }
+        self.expect_ecma_version("Async functions", EcmaVersion::Es2017)?;
+        let param = match self.parser_binding_identifier()? {
+            Some(identifier) => Pattern::Identifier(identifier),
+            None => return Err(self.expected(ParseErrorKind::IdentifierExpected)),
+        };
+        self.parse_arrow_function(start, vec![param], true)
+    }
+
+    /// Parse the `=>` and the body of an arrow function with `params`.
+    fn parse_arrow_function(
+        &mut self,
+        start: Position,
+        params: Vec<Pattern>,
+        is_async: bool,
+    ) -> Result<Expression, ParseError> {
+        // A line terminator is not allowed before the `=>`.
+        if self.peek_token() == Some(Token::Arrow) && self.peek_line_terminator() {
+            let found = self.next_token();
+            return Err(self.unexpected_token(found, vec![]));
+        }
+        self.expect_token(Token::Arrow)?;
+        self.expect_ecma_version("Arrow functions", EcmaVersion::Es2015)?;
+        check_duplicate_parameters(&params)?;
+        let body = if self.peek_token() == Some(Token::LeftBrace) {
+            ArrowFunctionBody::Block(self.parse_function_body(
+                &params,
+                is_async,
+                false,
+                Self::parse_block_statement,
+            )?)
+        } else {
+            let expression =
+                self.parse_function_body(&params, is_async, false, Self::parse_expression)?;
+            ArrowFunctionBody::Expression(expression)
+        };
+        Ok(Expression::ArrowFunctionExpression(Box::new(
+            ArrowFunctionExpression {
+                params,
+                body,
+                is_async,
+                span: self.span_from(start),
+            },
+        )))
+    }
+
+    /// Returns true if the code being parsed is strict mode code.
+    fn is_strict(&self) -> bool {
+        self.module || self.options.strict || self.in_class || self.use_strict
+    }
+
+    /// Returns true if `await` is an operator, which it is in async
+    /// functions and at the top level of modules.
+    fn is_await_operator(&self) -> bool {
+        self.context.in_async || (self.module && self.context.top_level)
+    }
+
+    /// Checks that `identifier` is not a word that is reserved here: `await`
+    /// in async functions and modules, `yield` in generators, the strict mode
+    /// reserved words, such as `static`, in strict mode code, and reserved
+    /// words written with escape sequences, such as `\u0069f`, anywhere.
+    fn check_identifier(&self, identifier: &Identifier) -> Result<(), ParseError> {
+        let reserved = match ContextualKeyword::from_word(&identifier.value) {
+            _ if Token::from_reserved_word(&identifier.value).is_some() => true,
+            Some(ContextualKeyword::Await) => self.context.in_async || self.module,
+            Some(ContextualKeyword::Yield) => self.context.in_generator || self.is_strict(),
+            Some(keyword) => keyword.is_strict_mode_reserved() && self.is_strict(),
+            None => false,
+        };
+        if reserved {
+            return Err(ParseError::new(
+                ParseErrorKind::ReservedWordAsIdentifier(identifier.value.clone()),
+                identifier.span,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that `identifier` can be bound here, which additionally
+    /// excludes `eval` and `arguments` in strict mode code.
+    fn check_binding_identifier(&self, identifier: &Identifier) -> Result<(), ParseError> {
+        self.check_identifier(identifier)?;
+        if self.is_strict() && matches!(identifier.value.as_str(), "eval" | "arguments") {
+            return Err(ParseError::new(
+                ParseErrorKind::EvalOrArgumentsInStrictMode(identifier.value.clone()),
+                identifier.span,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that the last consumed numeric or BigInt literal is allowed in
+    /// the targeted ECMAScript version and, for legacy octal literals, in
+    /// the current mode.
+    fn check_numeric_literal(&self) -> Result<(), ParseError> {
+        let raw = self.slice.as_str();
+        if raw.contains('_') {
+            self.expect_ecma_version("Numeric separators", EcmaVersion::Es2021)?;
+        }
+        let mut chars = raw.chars();
+        match (chars.next(), chars.next()) {
+            (Some('0'), Some('b' | 'B' | 'o' | 'O')) => {
+                self.expect_ecma_version("Binary and octal literals", EcmaVersion::Es2015)
+            }
+            (Some('0'), Some('0'..='9')) if self.is_strict() => {
+                let kind = if raw.bytes().all(|b| (b'0'..=b'7').contains(&b)) {
+                    ParseErrorKind::OctalLiteralInStrictMode
+                } else {
+                    ParseErrorKind::LeadingZeroInStrictMode
+                };
+                Err(ParseError::new(kind, self.span))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that the last consumed string literal has no legacy octal
+    /// escape sequences, or `\8` and `\9` escape sequences, in strict mode
+    /// code.
+    fn check_string_literal(&self) -> Result<(), ParseError> {
+        if !self.is_strict() {
+            return Ok(());
+        }
+        let mut chars = self.slice.chars().peekable();
+        while let Some(ch) = chars.next() {
+            if ch != '\\' {
+                continue;
+            }
+            let octal = match chars.next() {
+                Some('0') => matches!(chars.peek(), Some('0'..='9')),
+                Some('1'..='9') => true,
+                _ => false,
+            };
+            if octal {
+                return Err(ParseError::new(
+                    ParseErrorKind::OctalEscapeInStrictMode,
+                    self.span,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns an error for the last consumed token if `feature` requires a
+    /// later ECMAScript version than the targeted one.
+    fn expect_ecma_version(
+        &self,
+        feature: &'static str,
+        required: EcmaVersion,
+    ) -> Result<(), ParseError> {
+        if self.options.ecma_version >= required {
+            return Ok(());
+        }
+        let kind = ParseErrorKind::FeatureNotAvailable { feature, required };
+        Err(ParseError::new(kind, self.span))
+    }
+
+    /// Returns an error for the last consumed token, which starts the
+    /// TypeScript-only `syntax`, if TypeScript is disabled.
+    fn expect_typescript(&self, syntax: &'static str) -> Result<(), ParseError> {
+        if self.options.typescript {
+            return Ok(());
+        }
+        Err(ParseError::new(
+            ParseErrorKind::TypeScriptOnly(syntax),
+            self.span,
+        ))
+    }
+
+    /// Returns the span from `start` to the end of the last consumed token.
+    fn span_from(&self, start: Position) -> Span {
+        Span::new(start, self.span.end)
+    }
+
+    /// Returns an error of `kind` for the last consumed token `found`.
+    ///
+    /// If the token is invalid, the tokenizer error is reported instead,
+    /// because it describes the problem more precisely.
+    fn error(&self, found: Option<Token>, kind: ParseErrorKind) -> ParseError {
+        let kind = match found {
+            Some(Token::Error(error)) => error.into(),
+            _ => kind,
+        };
+        ParseError::new(kind, self.span)
+    }
+
+    /// Returns an error for the last consumed token `found`, which is not one
+    /// of the `expected` tokens.
+    fn unexpected_token(&self, found: Option<Token>, expected: Vec<Token>) -> ParseError {
+        let kind = ParseErrorKind::UnexpectedToken {
+            found: found.clone(),
+            expected,
+        };
+        self.error(found, kind)
+    }
+
+    /// Returns an error of `kind` for the next token, which has not been
+    /// consumed.
+    fn expected(&mut self, kind: ParseErrorKind) -> ParseError {
+        let kind = match self.peek_token() {
+            Some(Token::Error(error)) => error.into(),
+            _ => kind,
+        };
+        ParseError::new(kind, self.peek_span())
+    }
+
+    /// Reports a missing expression.
+    ///
+    /// When recovering from errors, the error is recorded and a placeholder
+    /// expression is returned in place of the missing one.
+    fn expression_expected(&mut self) -> Result<Expression, ParseError> {
+        let error = self.expected(ParseErrorKind::ExpressionExpected);
+        if !self.recover {
+            return Err(error);
+        }
+        // An invalid token has been reported, so skip it to avoid reporting
+        // it again.
+        if let Some(Token::Error(_)) = self.peek_token() {
+            self.next_token();
+        }
+        let span = Span::new(error.span.start, error.span.start);
+        self.errors.push(error);
+        Ok(Expression::Error(span))
+    }
+
+    /// Returns true if the next token is the contextual keyword `keyword`,
+    /// which is tokenized as an identifier.
+    fn peek_contextual_keyword(&mut self, keyword: ContextualKeyword) -> bool {
+        self.peek_token();
+        matches!(self.lookahead.borrow().front(), Some(lookahead) if lookahead.keyword == Some(keyword))
+    }
+
+    /// Returns the span of the next token, or an empty span at the end of
+    /// input.
+    fn peek_span(&mut self) -> Span {
+        self.peek_token();
+        match self.lookahead.borrow().front() {
+            Some(lookahead) => lookahead.span,
+            None => self.tokenizer.span(),
+        }
+    }
+
+    fn peek_token(&mut self) -> Option<Token> {
+        if let Some(lookahead) = self.lookahead.borrow().front() {
+            return Some(lookahead.token.clone());
+        }
+        let token = self.next_tokenizer_token()?;
+        let lookahead = self.tokenizer_lookahead(token.clone());
+        self.lookahead.borrow_mut().push_back(lookahead);
+        Some(token)
+    }
+
+    /// Returns the token after the next one, and whether there is a line
+    /// terminator before it, without consuming either of them.
+    fn peek_second_token(&mut self) -> Option<(Token, bool)> {
+        self.peek_token()?;
+        if let Some(lookahead) = self.lookahead.borrow().get(1) {
+            return Some((lookahead.token.clone(), lookahead.line_terminator_before));
+        }
+        let token = self.next_tokenizer_token()?;
+        let lookahead = self.tokenizer_lookahead(token.clone());
+        let line_terminator_before = lookahead.line_terminator_before;
+        self.lookahead.borrow_mut().push_back(lookahead);
+        Some((token, line_terminator_before))
+    }
+
+    /// Returns true if there is a line terminator between the last consumed
+    /// token and the next one, or before the end of input.
+    fn peek_line_terminator(&mut self) -> bool {
+        self.peek_token();
+        match self.lookahead.borrow().front() {
+            Some(lookahead) => lookahead.line_terminator_before,
+            None => self.tokenizer.has_line_terminator_before(),
+        }
+    }
+
+    /// Rescans the next token, which must be a `/` or `/=`, as a regular
+    /// expression literal and returns the new token.
+    fn rescan_regular_expression(&mut self) -> Token {
+        // The tokenizer is still at the lookahead token, which it rescans.
+        // A `/` is never read ahead as the second token, so it is the only
+        // lookahead.
+        let token = self.tokenizer.rescan_regular_expression();
+        let lookahead = self.tokenizer_lookahead(token.clone());
+        *self.lookahead.borrow_mut() = VecDeque::from([lookahead]);
+        token
+    }
+
+    /// Returns the current token of the tokenizer as a lookahead.
+    fn tokenizer_lookahead(&self, token: Token) -> Lookahead {
+        Lookahead {
+            token,
+            slice: self.tokenizer.slice().to_string(),
+            value: self.tokenizer.value().to_string(),
+            invalid_escape: self.tokenizer.has_invalid_escape(),
+            keyword: self.tokenizer.contextual_keyword(),
+            line_terminator_before: self.tokenizer.has_line_terminator_before(),
+            span: self.tokenizer.span(),
+        }
+    }
+
+    fn expect_token(&mut self, expected: Token) -> Result<(), ParseError> {
+        match self.next_token() {
+            Some(actual) if actual == expected => Ok(()),
+            actual => Err(self.unexpected_token(actual, vec![expected])),
+        }
+    }
+
+    fn next_token(&mut self) -> Option<Token> {
+        self.previous_span = self.span;
+        let lookahead = self.lookahead.borrow_mut().pop_front();
+        if let Some(lookahead) = lookahead {
+            self.slice = lookahead.slice;
+            self.value = lookahead.value;
+            self.invalid_escape = lookahead.invalid_escape;
+            self.keyword = lookahead.keyword;
+            self.token = Some(lookahead.token.clone());
+            self.line_terminator_before = lookahead.line_terminator_before;
+            self.span = lookahead.span;
+            return Some(lookahead.token);
+        }
+        let token = self.next_tokenizer_token();
+        self.slice.clear();
+        self.slice.push_str(self.tokenizer.slice());
+        self.value.clear();
+        self.value.push_str(self.tokenizer.value());
+        self.invalid_escape = self.tokenizer.has_invalid_escape();
+        self.keyword = self.tokenizer.contextual_keyword();
+        self.token = token.clone();
+        self.line_terminator_before = self.tokenizer.has_line_terminator_before();
+        self.span = self.tokenizer.span();
+        token
+    }
+
+    /// Puts the last consumed token back in front of the lookahead tokens,
+    /// so that it is the next token again.
+    ///
+    /// Only the source location of the token before it is restored, so this
+    /// is meant for error recovery, and can undo only one token.
+    fn unread_token(&mut self) {
+        let token = match self.token.take() {
+            Some(token) => token,
+            None => return,
+        };
+        let lookahead = Lookahead {
+            token,
+            slice: std::mem::take(&mut self.slice),
+            value: std::mem::take(&mut self.value),
+            invalid_escape: self.invalid_escape,
+            keyword: self.keyword.take(),
+            line_terminator_before: self.line_terminator_before,
+            span: self.span,
+        };
+        self.lookahead.borrow_mut().push_front(lookahead);
+        self.span = self.previous_span;
+    }
+
+    /// Returns the next token from the tokenizer that is not a comment.
+    ///
+    /// If comments are preserved, the comments before the token are recorded
+    /// in the comment table.
+    fn next_tokenizer_token(&mut self) -> Option<Token> {
+        loop {
+            let kind = match self.tokenizer.next_token()? {
+                Token::MultiLineComment => CommentKind::MultiLine,
+                Token::SingleLineComment => CommentKind::SingleLine,
+                token => return Some(token),
+            };
+            if self.options.preserve_comments {
+                let slice = self.tokenizer.slice();
+                let end = match kind {
+                    CommentKind::MultiLine => slice.len() - 2,
+                    CommentKind::SingleLine => slice.len(),
+                };
+                self.comments.push(Comment {
+                    kind,
+                    text: slice[2..end].to_string(),
+                    attachment: None,
+                    span: self.tokenizer.span(),
+                });
+            }
+        }
+    }
+}
+
+/// Returns true if `token` is a keyword that starts a statement, where error
+/// recovery can resume when it is on a new line.
+fn starts_statement(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::ClassKeyword
+            | Token::ConstKeyword
+            | Token::DoKeyword
+            | Token::ExportKeyword
+            | Token::ForKeyword
+            | Token::FunctionKeyword
+            | Token::IfKeyword
+            | Token::ImportKeyword
+            | Token::LetKeyword
+            | Token::ReturnKeyword
+            | Token::SwitchKeyword
+            | Token::ThrowKeyword
+            | Token::TryKeyword
+            | Token::VarKeyword
+            | Token::WhileKeyword
+    )
+}
+
+/// Returns the value of a numeric literal from its source text without
+/// numeric separators.
+fn numeric_value(text: &str) -> f64 {
+    let (digits, radix) = match text.get(..2) {
+        Some("0x" | "0X") => (&text[2..], 16),
+        Some("0o" | "0O") => (&text[2..], 8),
+        Some("0b" | "0B") => (&text[2..], 2),
+        // A legacy octal literal, such as `017`.
+        _ if text.len() > 1
+            && text.starts_with('0')
+            && text.bytes().all(|b| (b'0'..=b'7').contains(&b)) =>
+        {
+            (&text[1..], 8)
+        }
+        _ => return text.parse().unwrap_or(f64::NAN),
+    };
+    match u128::from_str_radix(digits, radix) {
+        Ok(value) => value as f64,
+        // Too large to represent exactly, so precision is lost anyway.
+        Err(_) => digits
+            .chars()
+            .filter_map(|ch| ch.to_digit(radix))
+            .fold(0.0, |value, digit| value * radix as f64 + digit as f64),
+    }
+}
+
+/// Checks that every declarator of a `const` or `using` declaration, and
+/// every destructuring declarator, has an initializer.
+fn check_initializers(declaration: &VariableStatement) -> Result<(), ParseError> {
+    let required = matches!(declaration.kind, VariableKind::Const | VariableKind::Using);
+    for declarator in &declaration.declarations {
+        let destructuring = !matches!(declarator.id, Pattern::Identifier(_));
+        if declarator.initializer.is_none() && (required || destructuring) {
+            return Err(ParseError::new(
+                ParseErrorKind::MissingInitializer,
+                declarator.span,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Returns true if `expression` is an identifier reference, which may be
+/// parenthesized.
+fn is_identifier_reference(expression: &Expression) -> bool {
+    match expression {
+        Expression::Identifier(_) => true,
+        Expression::ParenthesizedExpression(expression) => {
+            is_identifier_reference(&expression.expression)
+        }
+        _ => false,
+    }
+}
+
+/// Returns true if `expression` can be assigned to by an update expression,
+/// which is the case for an identifier reference or a property access that
+/// is not in an optional chain, which may be parenthesized.
+fn is_simple_assignment_target(expression: &Expression) -> bool {
+    match expression {
+        Expression::Identifier(_) | Expression::MemberExpression(_) => true,
+        Expression::ParenthesizedExpression(expression) => {
+            is_simple_assignment_target(&expression.expression)
+        }
+        _ => false,
+    }
+}
+
+/// Returns true if `key` is `__proto__`, which sets the prototype of an
+/// object literal unless the key is computed.
+fn is_proto_key(key: &PropertyKey) -> bool {
+    match key {
+        PropertyKey::Identifier(key) => key.value == "__proto__",
+        PropertyKey::StringLiteral(key) => key.value == "__proto__",
+        _ => false,
+    }
+}
+
+/// Returns true if `key` names a constructor, which is either the
+/// identifier `constructor` or the string literal `"constructor"`.
+fn is_constructor_key(key: &PropertyKey) -> bool {
+    match key {
+        PropertyKey::Identifier(key) => key.value == "constructor",
+        PropertyKey::StringLiteral(key) => key.value == "constructor",
+        _ => false,
+    }
+}
+
+/// Returns true if `key` is `prototype`, which cannot name a static class
+/// member unless the key is computed.
+fn is_prototype_key(key: &PropertyKey) -> bool {
+    match key {
+        PropertyKey::Identifier(key) => key.value == "prototype",
+        PropertyKey::StringLiteral(key) => key.value == "prototype",
+        _ => false,
+    }
+}
+
+/// Returns true if `operand`, an operand of a binary expression with `op`,
+/// mixes `??` with `||` or `&&` without parentheses.
+fn mixes_nullish_coalescing(op: &BinaryOp, operand: &Expression) -> bool {
+    let operand = match operand {
+        Expression::BinaryExpression(operand) => operand,
+        _ => return false,
+    };
+    matches!(
+        (op, &operand.op),
+        (
+            BinaryOp::NullishCoalescing,
+            BinaryOp::LogicalAnd | BinaryOp::LogicalOr
+        ) | (
+            BinaryOp::LogicalAnd | BinaryOp::LogicalOr,
+            BinaryOp::NullishCoalescing
+        )
+    )
+}
+
+/// Returns the assignment operator for a token, if the token is an
+/// assignment operator.
+fn assignment_operator(token: &Token) -> Option<AssignmentOp> {
+    let op = match token {
+        Token::AdditionAssignment => AssignmentOp::AdditionAssignment,
+        Token::Assignment => AssignmentOp::Assignment,
+        Token::BitwiseAndAssignment => AssignmentOp::BitwiseAndAssignment,
+        Token::BitwiseOrAssignment => AssignmentOp::BitwiseOrAssignment,
+        Token::BitwiseXorAssignment => AssignmentOp::BitwiseXorAssignment,
+        Token::DivisonAssignment => AssignmentOp::DivisionAssignment,
+        Token::ExponentationAssignment => AssignmentOp::ExponentiationAssignment,
+        Token::LeftShiftAssignment => AssignmentOp::LeftShiftAssignment,
+        Token::LogicalAndAssignment => AssignmentOp::LogicalAndAssignment,
+        Token::LogicalNullishAssignment => AssignmentOp::LogicalNullishAssignment,
+        Token::LogicalOrAssignment => AssignmentOp::LogicalOrAssignment,
+        Token::MultiplicationAssignment => AssignmentOp::MultiplicationAssignment,
+        Token::RemainderAssignment => AssignmentOp::RemainderAssignment,
+        Token::RightShiftAssignment => AssignmentOp::RightShiftAssignment,
+        Token::SubtractionAssignment => AssignmentOp::SubtractionAssignment,
+        Token::UnsignedRightShiftAssignment => AssignmentOp::UnsignedRightShiftAssignment,
+        _ => return None,
+    };
+    Some(op)
+}
+
+/// Returns an error if `pattern`, which was reinterpreted from an
+/// assignment target, contains a property access, which cannot be bound.
+fn check_binding_pattern(pattern: &Pattern) -> Result<(), ParseError> {
+    match pattern {
+        Pattern::ArrayPattern(pattern) => pattern
+            .elements
+            .iter()
+            .flatten()
+            .try_for_each(check_binding_pattern),
+        Pattern::AssignmentPattern(pattern) => check_binding_pattern(&pattern.left),
+        Pattern::Identifier(_) => Ok(()),
+        Pattern::MemberExpression(member) => Err(ParseError::new(
+            ParseErrorKind::IdentifierExpected,
+            member.span,
+        )),
+        Pattern::ObjectPattern(pattern) => {
+            pattern
+                .properties
+                .iter()
+                .try_for_each(|property| match property {
+                    ObjectPatternProperty::Property(property) => {
+                        check_binding_pattern(&property.value)
+                    }
+                    ObjectPatternProperty::RestElement(rest) => {
+                        check_binding_pattern(&rest.argument)
+                    }
+                })
+        }
+        Pattern::RestElement(rest) => check_binding_pattern(&rest.argument),
+    }
+}
+
+/// Returns true if every parameter in `params` is a plain identifier,
+/// without a default value, a rest element or destructuring.
+fn is_simple_parameter_list(params: &[Pattern]) -> bool {
+    params
+        .iter()
+        .all(|param| matches!(param, Pattern::Identifier(_)))
+}
+
+/// Returns an error for the first name in `params` that is bound by an
+/// earlier parameter as well.
+fn check_duplicate_parameters(params: &[Pattern]) -> Result<(), ParseError> {
+    let mut names = Vec::new();
+    for param in params {
+        bound_names(param, &mut names);
+    }
+    for (index, name) in names.iter().enumerate() {
+        if names[..index].iter().any(|other| other.value == name.value) {
+            return Err(ParseError::new(
+                ParseErrorKind::DuplicateParameter(name.value.clone()),
+                name.span,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Appends the identifiers that `pattern` binds to `names`, in source order.
+fn bound_names<'a>(pattern: &'a Pattern, names: &mut Vec<&'a Identifier>) {
+    match pattern {
+        Pattern::ArrayPattern(pattern) => {
+            for element in pattern.elements.iter().flatten() {
+                bound_names(element, names);
+            }
+        }
+        Pattern::AssignmentPattern(pattern) => bound_names(&pattern.left, names),
+        Pattern::Identifier(identifier) => names.push(identifier),
+        Pattern::MemberExpression(_) => {}
+        Pattern::ObjectPattern(pattern) => {
+            for property in &pattern.properties {
+                match property {
+                    ObjectPatternProperty::Property(property) => {
+                        bound_names(&property.value, names)
+                    }
+                    ObjectPatternProperty::RestElement(rest) => bound_names(&rest.argument, names),
+                }
+            }
+        }
+        Pattern::RestElement(rest) => bound_names(&rest.argument, names),
+    }
+}
+
+/// Returns the binary operator and its precedence for a token, if the token
+/// is a binary operator. Higher precedence binds more tightly.
+fn binary_operator(token: &Token) -> Option<(BinaryOp, u8)> {
+    let op = match token {
+        // `??` cannot be mixed with `||` and `&&`, so its precedence only
+        // matters relative to the other operators.
+        Token::NullishCoalescingOperator => (BinaryOp::NullishCoalescing, 1),
+        Token::LogicalOr => (BinaryOp::LogicalOr, 1),
+        Token::LogicalAnd => (BinaryOp::LogicalAnd, 2),
+        Token::Pipe => (BinaryOp::BitwiseOr, 3),
+        Token::Caret => (BinaryOp::BitwiseXor, 4),
+        Token::Ampersand => (BinaryOp::BitwiseAnd, 5),
+        Token::Equality => (BinaryOp::Equality, 6),
+        Token::Inequality => (BinaryOp::Inequality, 6),
+        Token::StrictEquality => (BinaryOp::StrictEquality, 6),
+        Token::StrictInequality => (BinaryOp::StrictInequality, 6),
+        Token::LeftAngleBracket => (BinaryOp::LessThan, 7),
+        Token::RightAngleBracket => (BinaryOp::GreaterThan, 7),
+        Token::LessThanOrEqual => (BinaryOp::LessThanOrEqual, 7),
+        Token::GreaterThanOrEqual => (BinaryOp::GreaterThanOrEqual, 7),
+        Token::InKeyword => (BinaryOp::In, 7),
+        Token::InstanceofKeyword => (BinaryOp::InstanceOf, 7),
+        Token::LeftShift => (BinaryOp::LeftShift, 8),
+        Token::RightShift => (BinaryOp::RightSift, 8),
+        Token::UnsignedRightShift => (BinaryOp::UnsignedRightShift, 8),
+        Token::Plus => (BinaryOp::Addition, 9),
+        Token::Minus => (BinaryOp::Subtraction, 9),
+        Token::Asterisk => (BinaryOp::Multiplication, 10),
+        Token::Slash => (BinaryOp::Division, 10),
+        Token::Percent => (BinaryOp::Remainder, 10),
+        Token::Exponentation => (BinaryOp::Exponentiation, 11),
+        _ => return None,
+    };
+    Some(op)
 }
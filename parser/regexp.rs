@@ -0,0 +1,800 @@
+// Copyright 2022 Pekka Enberg and contributors
+// SPDX-License-Identifier: MIT
+
+//! Regular expression validation
+//!
+//! The tokenizer only finds the end of a regular expression literal. The
+//! pattern and the flags are validated here against the grammar of
+//! ECMAScript patterns. Without the `u` and `v` flags, the pattern is
+//! validated with the web compatibility extensions of Annex B, which make,
+//! for example, a lone `{` or `]` a literal character.
+//!
+//! Only the syntax is validated: the names and values of Unicode property
+//! escapes, such as `\p{Script=Greek}`, are not checked.
+
+use crate::tokenizer::{is_identifier_part, is_identifier_start};
+use std::fmt;
+
+/// An error in a regular expression literal.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RegExpError {
+    /// A flag appears more than once.
+    DuplicateFlag(char),
+    /// Two groups with the same name can participate in the same match.
+    DuplicateGroupName(String),
+    /// Both the `u` and the `v` flag are set.
+    IncompatibleFlags,
+    /// A back-reference to a group number that does not exist.
+    InvalidBackReference,
+    /// A character class escape, such as `\d`, as the end of a range.
+    InvalidClassRange,
+    /// A character that must be escaped in a `v` mode character class.
+    InvalidClassSetCharacter,
+    /// A malformed escape sequence.
+    InvalidEscape,
+    /// A flag other than `d`, `g`, `i`, `m`, `s`, `u`, `v` and `y`.
+    InvalidFlag(char),
+    /// A `(?` that does not start a valid group.
+    InvalidGroup,
+    /// A malformed capture group name.
+    InvalidGroupName,
+    /// A malformed modifiers group, such as `(?-:a)`.
+    InvalidModifiers,
+    /// A malformed Unicode property escape.
+    InvalidPropertyName,
+    /// A malformed set operation in a `v` mode character class.
+    InvalidSetOperation,
+    /// A `{`, `}` or `]` that does not belong to a quantifier or a character
+    /// class, in Unicode mode.
+    LoneQuantifierBracket,
+    /// A string in a negated `v` mode character class, such as `[^\q{ab}]`.
+    NegatedClassWithStrings,
+    /// A quantifier without anything to repeat, such as `*a`.
+    NothingToRepeat,
+    /// A quantifier whose minimum is greater than its maximum.
+    NumbersOutOfOrder,
+    /// A character class range whose start is greater than its end.
+    RangeOutOfOrder,
+    /// A `)` without a matching `(`.
+    UnmatchedParenthesis,
+    /// A named back-reference to a group name that does not exist.
+    UnknownGroupName(String),
+    /// A character class without a closing `]`.
+    UnterminatedCharacterClass,
+    /// A group without a closing `)`.
+    UnterminatedGroup,
+}
+
+impl fmt::Display for RegExpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegExpError::DuplicateFlag(flag) => write!(f, "duplicate flag `{}`", flag),
+            RegExpError::DuplicateGroupName(name) => {
+                write!(f, "duplicate capture group name `{}`", name)
+            }
+            RegExpError::IncompatibleFlags => {
+                write!(f, "the `u` and `v` flags cannot be used together")
+            }
+            RegExpError::InvalidBackReference => write!(f, "invalid back-reference"),
+            RegExpError::InvalidClassRange => write!(f, "invalid character class in range"),
+            RegExpError::InvalidClassSetCharacter => {
+                write!(f, "invalid character in character class")
+            }
+            RegExpError::InvalidEscape => write!(f, "invalid escape"),
+            RegExpError::InvalidFlag(flag) => write!(f, "invalid flag `{}`", flag),
+            RegExpError::InvalidGroup => write!(f, "invalid group"),
+            RegExpError::InvalidGroupName => write!(f, "invalid capture group name"),
+            RegExpError::InvalidModifiers => write!(f, "invalid modifiers"),
+            RegExpError::InvalidPropertyName => write!(f, "invalid property name"),
+            RegExpError::InvalidSetOperation => write!(f, "invalid set operation"),
+            RegExpError::LoneQuantifierBracket => write!(f, "lone quantifier brackets"),
+            RegExpError::NegatedClassWithStrings => {
+                write!(f, "negated character class may contain strings")
+            }
+            RegExpError::NothingToRepeat => write!(f, "nothing to repeat"),
+            RegExpError::NumbersOutOfOrder => write!(f, "numbers out of order in quantifier"),
+            RegExpError::RangeOutOfOrder => {
+                write!(f, "range out of order in character class")
+            }
+            RegExpError::UnmatchedParenthesis => write!(f, "unmatched `)`"),
+            RegExpError::UnknownGroupName(name) => {
+                write!(f, "reference to unknown capture group `{}`", name)
+            }
+            RegExpError::UnterminatedCharacterClass => {
+                write!(f, "unterminated character class")
+            }
+            RegExpError::UnterminatedGroup => write!(f, "unterminated group"),
+        }
+    }
+}
+
+/// Validates a regular expression literal, given its pattern and flags.
+pub fn validate(pattern: &str, flags: &str) -> Result<(), RegExpError> {
+    let mut seen = String::new();
+    for flag in flags.chars() {
+        if !"dgimsuvy".contains(flag) {
+            return Err(RegExpError::InvalidFlag(flag));
+        }
+        if seen.contains(flag) {
+            return Err(RegExpError::DuplicateFlag(flag));
+        }
+        seen.push(flag);
+    }
+    let unicode = seen.contains('u');
+    let unicode_sets = seen.contains('v');
+    if unicode && unicode_sets {
+        return Err(RegExpError::IncompatibleFlags);
+    }
+    PatternValidator::new(pattern, unicode || unicode_sets, unicode_sets).validate()
+}
+
+/// Characters that can be escaped with an identity escape in Unicode mode.
+const SYNTAX_CHARACTERS: &str = "^$\\.*+?()[]{}|/";
+
+/// Characters that must be escaped in a `v` mode character class.
+const CLASS_SET_SYNTAX_CHARACTERS: &str = "()[]{}/-\\|";
+
+/// Characters that can be escaped in a `v` mode character class, and that
+/// must be escaped there when doubled.
+const CLASS_SET_RESERVED_PUNCTUATORS: &str = "&-!#%,:;<=>@`~";
+
+/// Characters that are reserved for future use when doubled in a `v` mode
+/// character class.
+const CLASS_SET_DOUBLE_PUNCTUATORS: &str = "&!#$%*+,.:;<=>?@^`~";
+
+/// An operand of a `v` mode character class.
+enum ClassSetOperand {
+    /// A single character, which can be the start or end of a range.
+    Character(char),
+    /// A nested class, a class escape or a string disjunction.
+    Set,
+}
+
+/// A recursive descent validator for a pattern.
+struct PatternValidator {
+    chars: Vec<char>,
+    pos: usize,
+    /// Whether the `u` or the `v` flag is set.
+    unicode_mode: bool,
+    /// Whether the `v` flag is set.
+    unicode_sets: bool,
+    /// Whether `\k` starts a named back-reference, which is the case in
+    /// Unicode mode and in patterns with named groups.
+    named_groups: bool,
+    group_count: u32,
+    /// Names of the groups that can participate in the same match as the
+    /// group that is being validated.
+    active_group_names: Vec<String>,
+    group_names: Vec<String>,
+    named_references: Vec<String>,
+    max_back_reference: u32,
+}
+
+impl PatternValidator {
+    fn new(pattern: &str, unicode_mode: bool, unicode_sets: bool) -> Self {
+        Self {
+            chars: pattern.chars().collect(),
+            pos: 0,
+            unicode_mode,
+            unicode_sets,
+            named_groups: false,
+            group_count: 0,
+            active_group_names: Vec::new(),
+            group_names: Vec::new(),
+            named_references: Vec::new(),
+            max_back_reference: 0,
+        }
+    }
+
+    fn validate(mut self) -> Result<(), RegExpError> {
+        self.named_groups = self.unicode_mode || self.has_named_groups();
+        self.parse_disjunction()?;
+        if self.pos < self.chars.len() {
+            return Err(RegExpError::UnmatchedParenthesis);
+        }
+        // Back-references can refer to groups that come after them, so they
+        // are checked once all groups are known.
+        if let Some(name) = self
+            .named_references
+            .iter()
+            .find(|name| !self.group_names.contains(name))
+        {
+            return Err(RegExpError::UnknownGroupName(name.clone()));
+        }
+        if self.unicode_mode && self.max_back_reference > self.group_count {
+            return Err(RegExpError::InvalidBackReference);
+        }
+        Ok(())
+    }
+
+    /// Returns true if the pattern has a named group.
+    fn has_named_groups(&self) -> bool {
+        let mut in_class = false;
+        let mut i = 0;
+        while i < self.chars.len() {
+            match self.chars[i] {
+                '\\' => i += 1,
+                '[' => in_class = true,
+                ']' => in_class = false,
+                '(' if !in_class
+                    && self.chars[i + 1..].starts_with(&['?', '<'])
+                    && !matches!(self.chars.get(i + 3), Some('=' | '!')) =>
+                {
+                    return true;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        false
+    }
+
+    fn parse_disjunction(&mut self) -> Result<(), RegExpError> {
+        // Groups in different alternatives never participate in the same
+        // match, so they may have the same name.
+        let base = self.active_group_names.len();
+        let mut names = Vec::new();
+        loop {
+            self.parse_alternative()?;
+            names.extend(self.active_group_names.drain(base..));
+            if !self.eat('|') {
+                break;
+            }
+        }
+        self.active_group_names.extend(names);
+        Ok(())
+    }
+
+    fn parse_alternative(&mut self) -> Result<(), RegExpError> {
+        while let Some(ch) = self.peek() {
+            if ch == '|' || ch == ')' {
+                break;
+            }
+            let quantifiable = self.parse_term()?;
+            if self.parse_quantifier()? && !quantifiable {
+                return Err(RegExpError::NothingToRepeat);
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses an assertion or an atom, and returns true if it can be
+    /// quantified.
+    fn parse_term(&mut self) -> Result<bool, RegExpError> {
+        match self.next() {
+            Some('^' | '$') => Ok(false),
+            Some('\\') => match self.peek() {
+                Some('b' | 'B') => {
+                    self.pos += 1;
+                    Ok(false)
+                }
+                _ => {
+                    self.parse_atom_escape()?;
+                    Ok(true)
+                }
+            },
+            Some('(') => self.parse_group(),
+            Some('[') => {
+                let negated = self.eat('^');
+                if self.unicode_sets {
+                    self.parse_class_set(negated)?;
+                } else {
+                    self.parse_class_ranges()?;
+                }
+                Ok(true)
+            }
+            Some('*' | '+' | '?') => Err(RegExpError::NothingToRepeat),
+            Some('{') => {
+                // Annex B allows a lone `{` as a literal character, but not
+                // a quantifier without an atom.
+                self.pos -= 1;
+                if self.parse_braced_quantifier()? {
+                    Err(RegExpError::NothingToRepeat)
+                } else if self.unicode_mode {
+                    Err(RegExpError::LoneQuantifierBracket)
+                } else {
+                    self.pos += 1;
+                    Ok(true)
+                }
+            }
+            Some('}' | ']') if self.unicode_mode => Err(RegExpError::LoneQuantifierBracket),
+            _ => Ok(true),
+        }
+    }
+
+    /// Parses a quantifier, if there is one, and returns true if there was.
+    fn parse_quantifier(&mut self) -> Result<bool, RegExpError> {
+        let quantifier = match self.peek() {
+            Some('*' | '+' | '?') => {
+                self.pos += 1;
+                true
+            }
+            Some('{') => {
+                let quantifier = self.parse_braced_quantifier()?;
+                if !quantifier && self.unicode_mode {
+                    return Err(RegExpError::LoneQuantifierBracket);
+                }
+                quantifier
+            }
+            _ => false,
+        };
+        if quantifier {
+            // A lazy quantifier.
+            self.eat('?');
+        }
+        Ok(quantifier)
+    }
+
+    /// Parses a `{n}`, `{n,}` or `{n,m}` quantifier, if there is one, and
+    /// returns true if there was.
+    fn parse_braced_quantifier(&mut self) -> Result<bool, RegExpError> {
+        let start = self.pos;
+        self.pos += 1;
+        let min = self.parse_decimal_digits();
+        let max = if self.eat(',') {
+            self.parse_decimal_digits().or(Some(u64::MAX))
+        } else {
+            min
+        };
+        match (min, max) {
+            (Some(min), Some(max)) if self.eat('}') => {
+                if min > max {
+                    return Err(RegExpError::NumbersOutOfOrder);
+                }
+                Ok(true)
+            }
+            _ => {
+                self.pos = start;
+                Ok(false)
+            }
+        }
+    }
+
+    fn parse_decimal_digits(&mut self) -> Option<u64> {
+        let mut value: Option<u64> = None;
+        while let Some(digit) = self.peek().and_then(|ch| ch.to_digit(10)) {
+            self.pos += 1;
+            let value = value.get_or_insert(0);
+            *value = value.saturating_mul(10).saturating_add(digit as u64);
+        }
+        value
+    }
+
+    /// Parses a group after its opening `(`, and returns true if it can be
+    /// quantified.
+    fn parse_group(&mut self) -> Result<bool, RegExpError> {
+        let mut quantifiable = true;
+        if self.eat('?') {
+            match self.next() {
+                Some(':') => {}
+                // Lookaheads are quantifiable only with Annex B.
+                Some('=' | '!') => quantifiable = !self.unicode_mode,
+                Some('<') if self.eat('=') || self.eat('!') => quantifiable = false,
+                Some('<') => {
+                    let name = self.parse_group_name()?;
+                    if self.active_group_names.contains(&name) {
+                        return Err(RegExpError::DuplicateGroupName(name));
+                    }
+                    if !self.group_names.contains(&name) {
+                        self.group_names.push(name.clone());
+                    }
+                    self.active_group_names.push(name);
+                    self.group_count += 1;
+                }
+                Some('i' | 'm' | 's' | '-') => {
+                    self.pos -= 1;
+                    self.parse_modifiers()?;
+                }
+                _ => return Err(RegExpError::InvalidGroup),
+            }
+        } else {
+            self.group_count += 1;
+        }
+        self.parse_disjunction()?;
+        if !self.eat(')') {
+            return Err(RegExpError::UnterminatedGroup);
+        }
+        Ok(quantifiable)
+    }
+
+    /// Parses the modifiers of a modifiers group, such as `i-m:` in
+    /// `(?i-m:a)`.
+    fn parse_modifiers(&mut self) -> Result<(), RegExpError> {
+        let mut added = String::new();
+        let mut removed = String::new();
+        let mut removing = false;
+        loop {
+            match self.next() {
+                Some(':') => break,
+                Some('-') if !removing => removing = true,
+                Some(flag @ ('i' | 'm' | 's')) => {
+                    if added.contains(flag) || removed.contains(flag) {
+                        return Err(RegExpError::InvalidModifiers);
+                    }
+                    if removing {
+                        removed.push(flag);
+                    } else {
+                        added.push(flag);
+                    }
+                }
+                _ => return Err(RegExpError::InvalidModifiers),
+            }
+        }
+        if removing && added.is_empty() && removed.is_empty() {
+            return Err(RegExpError::InvalidModifiers);
+        }
+        Ok(())
+    }
+
+    /// Parses a group name after its opening `<`, including the closing `>`.
+    fn parse_group_name(&mut self) -> Result<String, RegExpError> {
+        let mut name = String::new();
+        loop {
+            let ch = match self.next() {
+                Some('>') if !name.is_empty() => return Ok(name),
+                Some('\\') if self.eat('u') => self
+                    .parse_unicode_escape(true)
+                    .ok_or(RegExpError::InvalidGroupName)?,
+                Some(ch) => ch,
+                None => return Err(RegExpError::InvalidGroupName),
+            };
+            let valid = if name.is_empty() {
+                is_identifier_start(ch)
+            } else {
+                is_identifier_part(ch)
+            };
+            if !valid {
+                return Err(RegExpError::InvalidGroupName);
+            }
+            name.push(ch);
+        }
+    }
+
+    /// Parses an escape outside of a character class after the backslash.
+    fn parse_atom_escape(&mut self) -> Result<(), RegExpError> {
+        match self.peek() {
+            Some('1'..='9') => {
+                let reference = self.parse_decimal_digits().unwrap_or_default();
+                self.max_back_reference = self.max_back_reference.max(reference as u32);
+                Ok(())
+            }
+            Some('k') if self.named_groups => {
+                self.pos += 1;
+                if !self.eat('<') {
+                    return Err(RegExpError::InvalidEscape);
+                }
+                let name = self.parse_group_name()?;
+                self.named_references.push(name);
+                Ok(())
+            }
+            _ => self.parse_character_escape(false).map(|_| ()),
+        }
+    }
+
+    /// Parses a character escape or a character class escape after the
+    /// backslash, and returns the character that a character escape
+    /// denotes.
+    fn parse_character_escape(&mut self, in_class: bool) -> Result<Option<char>, RegExpError> {
+        let ch = self.next().ok_or(RegExpError::InvalidEscape)?;
+        let value = match ch {
+            'd' | 'D' | 's' | 'S' | 'w' | 'W' => return Ok(None),
+            'p' | 'P' if self.unicode_mode => {
+                self.parse_property_name()?;
+                return Ok(None);
+            }
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\u{b}',
+            'b' if in_class => '\u{8}',
+            '-' if in_class && self.unicode_mode => '-',
+            'c' => match self.peek() {
+                Some(letter) if letter.is_ascii_alphabetic() => {
+                    self.pos += 1;
+                    char::from(letter as u8 % 32)
+                }
+                // Annex B allows `\c` as a literal backslash followed by
+                // a `c`.
+                _ if !self.unicode_mode => {
+                    self.pos -= 1;
+                    '\\'
+                }
+                _ => return Err(RegExpError::InvalidEscape),
+            },
+            '0' if !matches!(self.peek(), Some('0'..='9')) => '\0',
+            '0'..='9' if self.unicode_mode => return Err(RegExpError::InvalidEscape),
+            '0'..='7' => {
+                // Annex B legacy octal escape, which is at most `\377`.
+                let max_len = if ch <= '3' { 3 } else { 2 };
+                let mut value = ch.to_digit(8).unwrap_or_default();
+                for _ in 1..max_len {
+                    match self.peek().and_then(|ch| ch.to_digit(8)) {
+                        Some(digit) => {
+                            self.pos += 1;
+                            value = value * 8 + digit;
+                        }
+                        None => break,
+                    }
+                }
+                char::from_u32(value).unwrap_or_default()
+            }
+            'x' => match self.parse_hex_digits(2) {
+                Some(value) => char::from_u32(value).unwrap_or_default(),
+                None if self.unicode_mode => return Err(RegExpError::InvalidEscape),
+                None => 'x',
+            },
+            'u' => match self.parse_unicode_escape(self.unicode_mode) {
+                Some(value) => value,
+                None if self.unicode_mode => return Err(RegExpError::InvalidEscape),
+                None => 'u',
+            },
+            ch if self.unicode_mode && !SYNTAX_CHARACTERS.contains(ch) => {
+                return Err(RegExpError::InvalidEscape)
+            }
+            ch => ch,
+        };
+        Ok(Some(value))
+    }
+
+    /// Parses a `\uXXXX` escape sequence after the `u`, or in Unicode mode
+    /// also a `\u{X...}` escape sequence or a surrogate pair written as two
+    /// escape sequences.
+    ///
+    /// Returns `None` and consumes nothing if the escape sequence is
+    /// malformed. Unpaired surrogates are decoded as U+FFFD.
+    fn parse_unicode_escape(&mut self, unicode_mode: bool) -> Option<char> {
+        let start = self.pos;
+        let value = if unicode_mode && self.eat('{') {
+            let mut value: u32 = 0;
+            let mut digits = 0;
+            while let Some(digit) = self.peek().and_then(|ch| ch.to_digit(16)) {
+                self.pos += 1;
+                value = value.saturating_mul(16).saturating_add(digit);
+                digits += 1;
+            }
+            if digits == 0 || value > 0x10FFFF || !self.eat('}') {
+                self.pos = start;
+                return None;
+            }
+            value
+        } else {
+            match self.parse_hex_digits(4) {
+                Some(value) => value,
+                None => {
+                    self.pos = start;
+                    return None;
+                }
+            }
+        };
+        if unicode_mode && (0xD800..0xDC00).contains(&value) {
+            let high_end = self.pos;
+            if self.eat('\\') && self.eat('u') {
+                if let Some(low @ 0xDC00..=0xDFFF) = self.parse_hex_digits(4) {
+                    let value = 0x10000 + ((value - 0xD800) << 10) + (low - 0xDC00);
+                    return char::from_u32(value);
+                }
+            }
+            self.pos = high_end;
+        }
+        Some(char::from_u32(value).unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    /// Parses exactly `count` hex digits and returns their value, or returns
+    /// `None` and consumes nothing if there are fewer hex digits.
+    fn parse_hex_digits(&mut self, count: usize) -> Option<u32> {
+        let mut value = 0;
+        for n in 0..count {
+            let digit = self.chars.get(self.pos + n)?.to_digit(16)?;
+            value = value * 16 + digit;
+        }
+        self.pos += count;
+        Some(value)
+    }
+
+    /// Parses the `{Name}` or `{Name=Value}` part of a Unicode property
+    /// escape.
+    fn parse_property_name(&mut self) -> Result<(), RegExpError> {
+        if !self.eat('{') {
+            return Err(RegExpError::InvalidPropertyName);
+        }
+        let mut name_len = 0;
+        let mut value_len = None;
+        loop {
+            match self.next() {
+                Some('}') => break,
+                Some('=') if value_len.is_none() && name_len > 0 => value_len = Some(0),
+                Some(ch) if ch.is_ascii_alphanumeric() || ch == '_' => match &mut value_len {
+                    Some(len) => *len += 1,
+                    None => name_len += 1,
+                },
+                _ => return Err(RegExpError::InvalidPropertyName),
+            }
+        }
+        if name_len == 0 || value_len == Some(0) {
+            return Err(RegExpError::InvalidPropertyName);
+        }
+        Ok(())
+    }
+
+    /// Parses the contents of a character class without the `v` flag, after
+    /// the opening `[` or `[^`, including the closing `]`.
+    fn parse_class_ranges(&mut self) -> Result<(), RegExpError> {
+        loop {
+            if self.eat(']') {
+                return Ok(());
+            }
+            let from = self.parse_class_atom()?;
+            if self.peek() == Some('-') && !matches!(self.peek_nth(1), Some(']') | None) {
+                self.pos += 1;
+                let to = self.parse_class_atom()?;
+                match (from, to) {
+                    (Some(from), Some(to)) if from > to => {
+                        return Err(RegExpError::RangeOutOfOrder)
+                    }
+                    (Some(_), Some(_)) => {}
+                    // Annex B allows a class escape in a range, which makes
+                    // the `-` a literal character.
+                    _ if self.unicode_mode => return Err(RegExpError::InvalidClassRange),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Parses a character or an escape in a character class, and returns
+    /// the character it denotes, or `None` for a character class escape.
+    fn parse_class_atom(&mut self) -> Result<Option<char>, RegExpError> {
+        match self.next() {
+            Some('\\') => self.parse_character_escape(true),
+            Some(ch) => Ok(Some(ch)),
+            None => Err(RegExpError::UnterminatedCharacterClass),
+        }
+    }
+
+    /// Parses the contents of a character class with the `v` flag, after
+    /// the opening `[` or `[^`, including the closing `]`.
+    ///
+    /// The contents are a union of operands and ranges, or operands that
+    /// are all joined with either `&&` or `--`.
+    fn parse_class_set(&mut self, negated: bool) -> Result<(), RegExpError> {
+        if self.eat(']') {
+            return Ok(());
+        }
+        let mut operand = self.parse_class_set_operand(negated)?;
+        for operator in ["&&", "--"] {
+            if self.at(operator) {
+                while self.eat_str(operator) {
+                    if self.peek() == Some(operator.chars().next().unwrap_or_default()) {
+                        return Err(RegExpError::InvalidSetOperation);
+                    }
+                    self.parse_class_set_operand(negated)?;
+                }
+                if !self.eat(']') {
+                    return Err(RegExpError::InvalidSetOperation);
+                }
+                return Ok(());
+            }
+        }
+        loop {
+            if let ClassSetOperand::Character(from) = operand {
+                if self.peek() == Some('-') && self.peek_nth(1) != Some('-') {
+                    self.pos += 1;
+                    match self.parse_class_set_operand(negated)? {
+                        ClassSetOperand::Character(to) if from > to => {
+                            return Err(RegExpError::RangeOutOfOrder)
+                        }
+                        ClassSetOperand::Character(_) => {}
+                        ClassSetOperand::Set => return Err(RegExpError::InvalidClassRange),
+                    }
+                }
+            }
+            if self.eat(']') {
+                return Ok(());
+            }
+            if self.at("&&") || self.at("--") {
+                return Err(RegExpError::InvalidSetOperation);
+            }
+            operand = self.parse_class_set_operand(negated)?;
+        }
+    }
+
+    fn parse_class_set_operand(&mut self, negated: bool) -> Result<ClassSetOperand, RegExpError> {
+        let ch = self.peek().ok_or(RegExpError::UnterminatedCharacterClass)?;
+        self.pos += 1;
+        match ch {
+            '[' => {
+                let negated = self.eat('^');
+                self.parse_class_set(negated)?;
+                Ok(ClassSetOperand::Set)
+            }
+            '\\' => match self.peek() {
+                Some('q') => {
+                    self.pos += 1;
+                    self.parse_class_string_disjunction(negated)?;
+                    Ok(ClassSetOperand::Set)
+                }
+                Some(ch) if CLASS_SET_RESERVED_PUNCTUATORS.contains(ch) => {
+                    self.pos += 1;
+                    Ok(ClassSetOperand::Character(ch))
+                }
+                _ => match self.parse_character_escape(true)? {
+                    Some(ch) => Ok(ClassSetOperand::Character(ch)),
+                    None => Ok(ClassSetOperand::Set),
+                },
+            },
+            ch if CLASS_SET_SYNTAX_CHARACTERS.contains(ch) => {
+                Err(RegExpError::InvalidClassSetCharacter)
+            }
+            ch if CLASS_SET_DOUBLE_PUNCTUATORS.contains(ch) && self.peek() == Some(ch) => {
+                Err(RegExpError::InvalidClassSetCharacter)
+            }
+            ch => Ok(ClassSetOperand::Character(ch)),
+        }
+    }
+
+    /// Parses a `\q{...}` string disjunction after the `q`.
+    fn parse_class_string_disjunction(&mut self, negated: bool) -> Result<(), RegExpError> {
+        if !self.eat('{') {
+            return Err(RegExpError::InvalidEscape);
+        }
+        let mut len = 0;
+        loop {
+            match self.peek() {
+                Some(ch @ ('}' | '|')) => {
+                    // A negated class can only contain single characters.
+                    if negated && len != 1 {
+                        return Err(RegExpError::NegatedClassWithStrings);
+                    }
+                    self.pos += 1;
+                    if ch == '}' {
+                        return Ok(());
+                    }
+                    len = 0;
+                }
+                Some('[') => return Err(RegExpError::InvalidClassSetCharacter),
+                Some(_) => match self.parse_class_set_operand(negated)? {
+                    ClassSetOperand::Character(_) => len += 1,
+                    ClassSetOperand::Set => return Err(RegExpError::InvalidEscape),
+                },
+                None => return Err(RegExpError::UnterminatedCharacterClass),
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.chars.get(self.pos + n).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += 1;
+        Some(ch)
+    }
+
+    fn eat(&mut self, ch: char) -> bool {
+        if self.peek() == Some(ch) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn at(&self, s: &str) -> bool {
+        s.chars()
+            .enumerate()
+            .all(|(n, ch)| self.peek_nth(n) == Some(ch))
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        if self.at(s) {
+            self.pos += s.chars().count();
+            return true;
+        }
+        false
+    }
+}
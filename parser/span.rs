@@ -0,0 +1,60 @@
+// Copyright 2022 Pekka Enberg and contributors
+// SPDX-License-Identifier: MIT
+
+//! Source locations
+//!
+//! Every token and AST node records the region of source text it was parsed
+//! from as a `Span`, which is a pair of `Position`s.
+
+use std::fmt;
+
+/// A position in the source text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    /// Byte offset from the beginning of the source text.
+    pub offset: usize,
+    /// Line number, starting from 1.
+    pub line: usize,
+    /// Column number in characters, starting from 0.
+    pub column: usize,
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            line: 1,
+            column: 0,
+        }
+    }
+}
+
+/// A region of source text.
+///
+/// The `start` position is inclusive and the `end` position is exclusive.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Span {
+    pub fn new(start: Position, end: Position) -> Self {
+        Self { start, end }
+    }
+}
+
+impl fmt::Debug for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}..{} ({}:{}-{}:{})",
+            self.start.offset,
+            self.end.offset,
+            self.start.line,
+            self.start.column,
+            self.end.line,
+            self.end.column
+        )
+    }
+}
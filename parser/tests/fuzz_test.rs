@@ -0,0 +1,98 @@
+// Copyright 2022 Pekka Enberg and contributors
+// SPDX-License-Identifier: MIT
+
+//! Checks that no input makes the parser panic.
+//!
+//! The corpus consists of the sample programs and lit tests in the
+//! repository and a handful of malformed snippets. Every prefix of every
+//! corpus entry, and every variant with a single character removed or
+//! replaced, is parsed as both a script and a module, with and without error
+//! recovery. Parsing is allowed to fail, but not to panic.
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+    use tsparser_parser::parser::{Parser, ParserOptions, SourceType};
+    use tsparser_parser::tokenizer::Tokenizer;
+    use utf8_chars::BufReadCharsExt;
+
+    const SNIPPETS: &[&str] = &[
+        "#",
+        "@",
+        "`",
+        "let 1",
+        "let x = ",
+        "let x = (a",
+        "let x = a +* b;",
+        "import { from",
+        "import { a, } from 'b",
+        "\"unterminated",
+        "'\\",
+        "a ** b ** (c",
+        "/* a */ let x = /* b */ 1; // c",
+        "/(?<a>[a-z\\d]{1,2})\\k<a>/u; a / b /= c",
+        "async function* f(a = 1, [b, ...c], { d: e }) { (f, ...g) => { h; }; }",
+        "class A extends B { static #a = 1; get b() {} static { c; } async *[d]() {} }",
+        "a: for (var b in c) { switch (b) { case 1: continue a; default: break; } } try { throw d; } catch { } finally { }",
+    ];
+
+    /// Characters that are substituted into the corpus entries.
+    const REPLACEMENTS: &[char] = &['(', ')', '{', '}', '"', '\'', '`', '/', '\\', '#', '\n'];
+
+    fn parse(source: &str) {
+        for source_type in [SourceType::Script, SourceType::Module] {
+            for recover in [false, true] {
+                // Comment attachment is exercised together with recovery.
+                let options = ParserOptions::new()
+                    .source_type(source_type)
+                    .recover(recover)
+                    .preserve_comments(recover);
+                let mut input = source.as_bytes();
+                let tokenizer = Tokenizer::new(input.chars());
+                let _ = Parser::with_options(tokenizer, options).parse();
+            }
+        }
+    }
+
+    fn fuzz(source: &str) {
+        let chars: Vec<char> = source.chars().collect();
+        for i in 0..=chars.len() {
+            parse(&chars[..i].iter().collect::<String>());
+        }
+        for i in 0..chars.len() {
+            let mut removed = chars.clone();
+            removed.remove(i);
+            parse(&removed.iter().collect::<String>());
+            for replacement in REPLACEMENTS {
+                let mut replaced = chars.clone();
+                replaced[i] = *replacement;
+                parse(&replaced.iter().collect::<String>());
+            }
+        }
+    }
+
+    fn corpus() -> Vec<String> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let mut corpus: Vec<String> = SNIPPETS.iter().map(|s| s.to_string()).collect();
+        for dir in ["tests", "cli/tests/lit"] {
+            for entry in fs::read_dir(root.join(dir)).unwrap() {
+                let source = fs::read_to_string(entry.unwrap().path()).unwrap();
+                // Strip the lit test directives, which are just comments.
+                let source: Vec<&str> = source
+                    .lines()
+                    .filter(|line| !line.starts_with("// CHECK") && !line.starts_with("// RUN"))
+                    .collect();
+                corpus.push(source.join("\n"));
+            }
+        }
+        corpus
+    }
+
+    #[test]
+    fn no_panics() {
+        for source in corpus() {
+            fuzz(&source);
+        }
+    }
+}
@@ -10,8 +10,10 @@
 //! The tokenization is implemented lazily. The tokenizer transforms the
 //! character stream one token at a time.
 
+use crate::span::{Position, Span};
 use std::collections::vec_deque::VecDeque;
 use std::io::BufRead;
+use unicode_xid::UnicodeXID;
 use utf8_chars::Chars;
 
 /// ECMAScript token enumeration.
@@ -22,25 +24,47 @@ pub enum Token {
     Arrow,                        // =>
     Assignment,                   // =
     Asterisk,                     // *
+    BigIntLiteral,                // BigInt literal. For example, 1n
     BitwiseAndAssignment,         // &=
     BitwiseOrAssignment,          // |=
     BitwiseXorAssignment,         // ^=
+    BreakKeyword,                 // break
     Caret,                        // ^
+    CaseKeyword,                  // case
+    CatchKeyword,                 // catch
+    ClassKeyword,                 // class
     Colon,                        // :
     Comma,                        // ,
     ConstKeyword,                 // const
+    ContinueKeyword,              // continue
+    DebuggerKeyword,              // debugger
     Decrement,                    // --
+    DefaultKeyword,               // default
+    DeleteKeyword,                // delete
     DivisonAssignment,            // /=
+    DoKeyword,                    // do
     Dot,                          // .
+    ElseKeyword,                  // else
+    EnumKeyword,                  // enum
     Equality,                     // ==
+    Error(TokenError),            // Invalid token
     ExclamationMark,              // !
+    ExportKeyword,                // export
     Exponentation,                // **
     ExponentationAssignment,      // **=
+    ExtendsKeyword,               // extends
+    FalseKeyword,                 // false
+    FinallyKeyword,               // finally
+    ForKeyword,                   // for
+    FunctionKeyword,              // function
     GreaterThanOrEqual,           // >=
     IdentifierName,               // Identifier.
+    IfKeyword,                    // if
     ImportKeyword,                // import
+    InKeyword,                    // in
     Increment,                    // ++
     Inequality,                   // !=
+    InstanceofKeyword,            // instanceof
     LeftAngleBracket,             // <
     LeftBrace,                    // {
     LeftParenthesis,              // (
@@ -57,14 +81,19 @@ pub enum Token {
     Minus,                        // -
     MultiLineComment,             // /* [...] */
     MultiplicationAssignment,     // *=
+    NewKeyword,                   // new
+    NullKeyword,                  // null
     NullishCoalescingOperator,    // ??
     NumericLiteral,               // Numeric literal
     OptionalChaining,             // ?.
     Percent,                      // %
     Pipe,                         // |
     Plus,                         // +
+    PrivateIdentifier,            // Private identifier. For example, #a
     QuestionMark,                 // ?
     RemainderAssignment,          // %=
+    RegularExpressionLiteral,     // Regular expression literal. For example, /ab+c/g
+    ReturnKeyword,                // return
     RightAngleBracket,            // >
     RightBrace,                   // }
     RightParenthesis,             // )
@@ -79,17 +108,254 @@ pub enum Token {
     StrictInequality,             // !==
     StringLiteral,                // String literal. For example, "hello, world"
     SubtractionAssignment,        // -=
+    SuperKeyword,                 // super
+    SwitchKeyword,                // switch
+    TemplateHead,                 // Template head. For example: `hello, ${
     TemplateLiteral,              // Template literal. For example: `hello, world`
+    TemplateMiddle,               // Template middle. For example: }, ${
+    TemplateTail,                 // Template tail. For example: }, world`
+    ThisKeyword,                  // this
+    ThrowKeyword,                 // throw
     Tilde,                        // ~
+    TrueKeyword,                  // true
+    TryKeyword,                   // try
+    TypeofKeyword,                // typeof
     UnsignedRightShift,           // >>>
     UnsignedRightShiftAssignment, // >>>=
     VarKeyword,                   // var
+    VoidKeyword,                  // void
+    WhileKeyword,                 // while
+    WithKeyword,                  // with
+}
+
+impl Token {
+    /// Returns the keyword token of the reserved word spelled `word`, if
+    /// there is one.
+    pub fn from_reserved_word(word: &str) -> Option<Self> {
+        RESERVED_WORDS
+            .binary_search_by(|(reserved, _)| reserved.cmp(&word))
+            .ok()
+            .map(|index| RESERVED_WORDS[index].1.clone())
+    }
+
+    /// Returns true if this token is a keyword.
+    ///
+    /// Keywords are identifier names, so they are allowed where any identifier
+    /// name is, for example, as property names and import names.
+    pub fn is_keyword(&self) -> bool {
+        matches!(
+            self,
+            Token::BreakKeyword
+                | Token::CaseKeyword
+                | Token::CatchKeyword
+                | Token::ClassKeyword
+                | Token::ConstKeyword
+                | Token::ContinueKeyword
+                | Token::DebuggerKeyword
+                | Token::DefaultKeyword
+                | Token::DeleteKeyword
+                | Token::DoKeyword
+                | Token::ElseKeyword
+                | Token::EnumKeyword
+                | Token::ExportKeyword
+                | Token::ExtendsKeyword
+                | Token::FalseKeyword
+                | Token::FinallyKeyword
+                | Token::ForKeyword
+                | Token::FunctionKeyword
+                | Token::IfKeyword
+                | Token::ImportKeyword
+                | Token::InKeyword
+                | Token::InstanceofKeyword
+                | Token::LetKeyword
+                | Token::NewKeyword
+                | Token::NullKeyword
+                | Token::ReturnKeyword
+                | Token::SuperKeyword
+                | Token::SwitchKeyword
+                | Token::ThisKeyword
+                | Token::ThrowKeyword
+                | Token::TrueKeyword
+                | Token::TryKeyword
+                | Token::TypeofKeyword
+                | Token::VarKeyword
+                | Token::VoidKeyword
+                | Token::WhileKeyword
+                | Token::WithKeyword
+        )
+    }
+}
+
+/// The reserved words, which are always tokenized as keywords, sorted for
+/// binary search.
+const RESERVED_WORDS: &[(&str, Token)] = &[
+    ("break", Token::BreakKeyword),
+    ("case", Token::CaseKeyword),
+    ("catch", Token::CatchKeyword),
+    ("class", Token::ClassKeyword),
+    ("const", Token::ConstKeyword),
+    ("continue", Token::ContinueKeyword),
+    ("debugger", Token::DebuggerKeyword),
+    ("default", Token::DefaultKeyword),
+    ("delete", Token::DeleteKeyword),
+    ("do", Token::DoKeyword),
+    ("else", Token::ElseKeyword),
+    ("enum", Token::EnumKeyword),
+    ("export", Token::ExportKeyword),
+    ("extends", Token::ExtendsKeyword),
+    ("false", Token::FalseKeyword),
+    ("finally", Token::FinallyKeyword),
+    ("for", Token::ForKeyword),
+    ("function", Token::FunctionKeyword),
+    ("if", Token::IfKeyword),
+    ("import", Token::ImportKeyword),
+    ("in", Token::InKeyword),
+    ("instanceof", Token::InstanceofKeyword),
+    ("let", Token::LetKeyword),
+    ("new", Token::NewKeyword),
+    ("null", Token::NullKeyword),
+    ("return", Token::ReturnKeyword),
+    ("super", Token::SuperKeyword),
+    ("switch", Token::SwitchKeyword),
+    ("this", Token::ThisKeyword),
+    ("throw", Token::ThrowKeyword),
+    ("true", Token::TrueKeyword),
+    ("try", Token::TryKeyword),
+    ("typeof", Token::TypeofKeyword),
+    ("var", Token::VarKeyword),
+    ("void", Token::VoidKeyword),
+    ("while", Token::WhileKeyword),
+    ("with", Token::WithKeyword),
+];
+
+/// A word that is tokenized as an identifier name, but has a special
+/// meaning in some contexts, such as `of` in `for (a of b) {}`, or is
+/// reserved in strict mode code, such as `static`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContextualKeyword {
+    Abstract,   // abstract (TypeScript)
+    Accessor,   // accessor
+    As,         // as
+    Async,      // async
+    Await,      // await
+    Declare,    // declare (TypeScript)
+    From,       // from
+    Get,        // get
+    Implements, // implements
+    Interface,  // interface
+    Meta,       // meta
+    Module,     // module (TypeScript)
+    Namespace,  // namespace (TypeScript)
+    Of,         // of
+    Package,    // package
+    Private,    // private
+    Protected,  // protected
+    Public,     // public
+    Readonly,   // readonly (TypeScript)
+    Set,        // set
+    Static,     // static
+    Target,     // target
+    Type,       // type (TypeScript)
+    Using,      // using
+    Yield,      // yield
+}
+
+/// The contextual keywords, sorted for binary search.
+const CONTEXTUAL_KEYWORDS: &[(&str, ContextualKeyword)] = &[
+    ("abstract", ContextualKeyword::Abstract),
+    ("accessor", ContextualKeyword::Accessor),
+    ("as", ContextualKeyword::As),
+    ("async", ContextualKeyword::Async),
+    ("await", ContextualKeyword::Await),
+    ("declare", ContextualKeyword::Declare),
+    ("from", ContextualKeyword::From),
+    ("get", ContextualKeyword::Get),
+    ("implements", ContextualKeyword::Implements),
+    ("interface", ContextualKeyword::Interface),
+    ("meta", ContextualKeyword::Meta),
+    ("module", ContextualKeyword::Module),
+    ("namespace", ContextualKeyword::Namespace),
+    ("of", ContextualKeyword::Of),
+    ("package", ContextualKeyword::Package),
+    ("private", ContextualKeyword::Private),
+    ("protected", ContextualKeyword::Protected),
+    ("public", ContextualKeyword::Public),
+    ("readonly", ContextualKeyword::Readonly),
+    ("set", ContextualKeyword::Set),
+    ("static", ContextualKeyword::Static),
+    ("target", ContextualKeyword::Target),
+    ("type", ContextualKeyword::Type),
+    ("using", ContextualKeyword::Using),
+    ("yield", ContextualKeyword::Yield),
+];
+
+impl ContextualKeyword {
+    /// Returns the contextual keyword spelled `word`, if there is one.
+    pub fn from_word(word: &str) -> Option<Self> {
+        CONTEXTUAL_KEYWORDS
+            .binary_search_by(|(keyword, _)| keyword.cmp(&word))
+            .ok()
+            .map(|index| CONTEXTUAL_KEYWORDS[index].1)
+    }
+
+    /// Returns true if this keyword is reserved in strict mode code, where
+    /// it cannot be used as an identifier.
+    pub fn is_strict_mode_reserved(self) -> bool {
+        matches!(
+            self,
+            ContextualKeyword::Implements
+                | ContextualKeyword::Interface
+                | ContextualKeyword::Package
+                | ContextualKeyword::Private
+                | ContextualKeyword::Protected
+                | ContextualKeyword::Public
+                | ContextualKeyword::Static
+                | ContextualKeyword::Yield
+        )
+    }
+}
+
+/// An error in the character stream that prevents forming a valid token.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenError {
+    ConsecutiveNumericSeparators,
+    DigitExpected,
+    IdentifierAfterNumericLiteral,
+    InvalidCharacter(char),
+    InvalidEscapeSequence,
+    NumericSeparatorNotAllowed,
+    UnterminatedComment,
+    UnterminatedRegularExpressionLiteral,
+    UnterminatedStringLiteral,
+    UnterminatedTemplateLiteral,
 }
 
 pub struct Tokenizer<'a, R: BufRead> {
     chars: Chars<'a, R>,
     lookaheads: VecDeque<char>,
     slice: String,
+    /// Cooked value of the current token.
+    value: String,
+    /// Whether the current token is a template with an invalid escape
+    /// sequence.
+    invalid_escape: bool,
+    /// The contextual keyword that the current token spells, if it is an
+    /// identifier name.
+    contextual_keyword: Option<ContextualKeyword>,
+    /// Whether there is a line terminator between the current token and the
+    /// previous token that is not a comment, either in whitespace or in a
+    /// multi-line comment.
+    line_terminator_before: bool,
+    /// Whether the previous token is a comment.
+    after_comment: bool,
+    /// Number of unclosed braces in each template substitution that is
+    /// being tokenized, innermost last. A `}` closes the substitution only
+    /// when there are no unclosed braces in it.
+    template_braces: Vec<usize>,
+    /// Position of the next character in the character stream.
+    position: Position,
+    /// Start position of the current token.
+    start: Position,
 }
 
 impl<'a, R: BufRead> Tokenizer<'a, R> {
@@ -98,6 +364,14 @@ impl<'a, R: BufRead> Tokenizer<'a, R> {
             chars,
             lookaheads: VecDeque::new(),
             slice: String::new(),
+            value: String::new(),
+            invalid_escape: false,
+            contextual_keyword: None,
+            line_terminator_before: false,
+            after_comment: false,
+            template_braces: Vec::new(),
+            position: Position::default(),
+            start: Position::default(),
         }
     }
 
@@ -105,21 +379,43 @@ impl<'a, R: BufRead> Tokenizer<'a, R> {
     ///
     /// The tokenizer ignores any whitespace.
     pub fn next_token(&mut self) -> Option<Token> {
-        self.get_next_token()
+        // Comments are skipped by the parser, so the line terminators before
+        // and inside them belong to the next token that is not a comment.
+        if !self.after_comment {
+            self.line_terminator_before = false;
+        }
+        let token = self.get_next_token();
+        self.after_comment = matches!(
+            token,
+            Some(Token::MultiLineComment | Token::SingleLineComment)
+        );
+        if token == Some(Token::MultiLineComment) && self.slice.chars().any(is_line_terminator) {
+            self.line_terminator_before = true;
+        }
+        token
     }
 
     fn get_next_token(&mut self) -> Option<Token> {
-        let mut ch = self.next_char();
+        let mut ch = self.peek_char();
         while let Some(c) = ch {
-            if !c.is_whitespace() {
+            if !is_whitespace(c) && !is_line_terminator(c) {
                 break;
             }
-            ch = self.consume_char_and_peek();
+            if is_line_terminator(c) {
+                self.line_terminator_before = true;
+            }
+            self.skip_char();
+            ch = self.peek_char();
         }
         self.slice.clear();
+        self.value.clear();
+        self.invalid_escape = false;
+        self.contextual_keyword = None;
+        self.start = self.position;
         match ch {
-            Some(ch) if ch.is_alphabetic() => self.consume_identifier(),
-            Some(ch) if ch.is_numeric() => self.consume_numeric_literal(),
+            Some(ch) if is_identifier_start(ch) => self.consume_identifier(),
+            Some('\\') if self.peek_nth_char(1) == Some('u') => self.consume_identifier(),
+            Some(ch) if ch.is_ascii_digit() => self.consume_numeric_literal(),
             Some('!') => match self.consume_char_and_peek() {
                 Some('=') => match self.consume_char_and_peek() {
                     Some('=') => self.consume_char_as(Token::StrictInequality),
@@ -128,6 +424,7 @@ impl<'a, R: BufRead> Tokenizer<'a, R> {
                 _ => Some(Token::ExclamationMark),
             },
             Some('"') => self.consume_double_quote_string_literal(),
+            Some('#') => self.consume_private_identifier(),
             Some('%') => match self.consume_char_and_peek() {
                 Some('=') => self.consume_char_as(Token::RemainderAssignment),
                 _ => Some(Token::Percent),
@@ -163,7 +460,9 @@ impl<'a, R: BufRead> Tokenizer<'a, R> {
                 _ => Some(Token::Minus),
             },
             Some('.') => {
-                if self.peek_char() == Some('.') && self.peek_char() == Some('.') {
+                if self.peek_nth_char(1).is_some_and(|ch| ch.is_ascii_digit()) {
+                    self.consume_numeric_literal()
+                } else if self.peek_nth_char(1) == Some('.') && self.peek_nth_char(2) == Some('.') {
                     self.consume_char();
                     self.consume_char();
                     self.consume_char_as(Token::Spread)
@@ -176,6 +475,10 @@ impl<'a, R: BufRead> Tokenizer<'a, R> {
                     self.consume_char();
                     self.consume_single_line_comment()
                 }
+                Some('*') => {
+                    self.consume_char();
+                    self.consume_multi_line_comment()
+                }
                 Some('=') => self.consume_char_as(Token::DivisonAssignment),
                 _ => Some(Token::Slash),
             },
@@ -223,16 +526,27 @@ impl<'a, R: BufRead> Tokenizer<'a, R> {
                         Some(Token::RightShift)
                     }
                 } else {
-                    Some(Token::RightSquareBracket)
+                    Some(Token::RightAngleBracket)
                 }
             }
-            Some('}') => self.consume_char_as(Token::RightBrace),
+            Some('}') => match self.template_braces.last_mut() {
+                Some(0) => self.consume_template(true),
+                Some(braces) => {
+                    *braces -= 1;
+                    self.consume_char_as(Token::RightBrace)
+                }
+                None => self.consume_char_as(Token::RightBrace),
+            },
             Some('?') => match self.consume_char_and_peek() {
                 Some('?') => match self.consume_char_and_peek() {
                     Some('=') => self.consume_char_as(Token::LogicalNullishAssignment),
                     _ => Some(Token::NullishCoalescingOperator),
                 },
-                Some('.') => self.consume_char_as(Token::OptionalChaining),
+                // `?.` followed by a digit is a `?` and a numeric literal,
+                // as in `a?.5:b`.
+                Some('.') if !matches!(self.peek_nth_char(1), Some('0'..='9')) => {
+                    self.consume_char_as(Token::OptionalChaining)
+                }
                 _ => Some(Token::QuestionMark),
             },
             Some('[') => self.consume_char_as(Token::LeftSquareBracket),
@@ -241,7 +555,12 @@ impl<'a, R: BufRead> Tokenizer<'a, R> {
                 Some('=') => self.consume_char_as(Token::BitwiseXorAssignment),
                 _ => Some(Token::Caret),
             },
-            Some('{') => self.consume_char_as(Token::LeftBrace),
+            Some('{') => {
+                if let Some(braces) = self.template_braces.last_mut() {
+                    *braces += 1;
+                }
+                self.consume_char_as(Token::LeftBrace)
+            }
             Some('|') => match self.consume_char_and_peek() {
                 Some('|') => match self.consume_char_and_peek() {
                     Some('=') => self.consume_char_as(Token::LogicalOrAssignment),
@@ -251,76 +570,541 @@ impl<'a, R: BufRead> Tokenizer<'a, R> {
                 _ => Some(Token::Pipe),
             },
             Some('~') => self.consume_char_as(Token::Tilde),
-            Some(ch) => {
-                todo!("Token starting with character `{}` is not recognized", ch)
-            }
+            Some('`') => self.consume_template(false),
+            Some(ch) => self.consume_char_as(Token::Error(TokenError::InvalidCharacter(ch))),
             None => None,
         }
     }
 
+    /// Rescans the current token, which must be a `/` or `/=`, as a regular
+    /// expression literal and returns the new token.
+    ///
+    /// A `/` cannot be told apart from the start of a regular expression
+    /// literal without knowing the syntactic context, so the tokenizer
+    /// always scans it as a division operator, and the parser calls this
+    /// where a regular expression literal is allowed instead.
+    pub fn rescan_regular_expression(&mut self) -> Token {
+        let mut in_class = false;
+        loop {
+            match self.peek_char() {
+                None | Some('\n' | '\r' | '\u{2028}' | '\u{2029}') => {
+                    return Token::Error(TokenError::UnterminatedRegularExpressionLiteral)
+                }
+                Some('\\') => {
+                    self.consume_char();
+                    if matches!(
+                        self.peek_char(),
+                        None | Some('\n' | '\r' | '\u{2028}' | '\u{2029}')
+                    ) {
+                        return Token::Error(TokenError::UnterminatedRegularExpressionLiteral);
+                    }
+                }
+                Some('[') => in_class = true,
+                Some(']') => in_class = false,
+                Some('/') if !in_class => break,
+                Some(_) => {}
+            }
+            self.consume_char();
+        }
+        self.consume_char();
+        while let Some(ch) = self.peek_char() {
+            if !is_identifier_part(ch) {
+                break;
+            }
+            self.consume_char();
+        }
+        Token::RegularExpressionLiteral
+    }
+
+    /// Returns the source text of the current token.
     pub fn slice(&self) -> &str {
         &self.slice
     }
 
+    /// Returns the cooked value of the current token.
+    ///
+    /// For string literals, this is the value of the string, without the
+    /// quotes and with escape sequences decoded. For numeric and BigInt
+    /// literals, this is the source text without numeric separators and
+    /// without the BigInt suffix `n`. For templates, this is the cooked
+    /// string between the delimiters. For identifier names, including
+    /// keywords, this is the name with escape sequences decoded, and for
+    /// private identifiers, the name without the `#`. For other tokens, the
+    /// value is empty.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Returns true if the current token is a template with an invalid
+    /// escape sequence.
+    ///
+    /// Such templates are allowed only in tagged templates, where their
+    /// cooked value is undefined.
+    pub fn has_invalid_escape(&self) -> bool {
+        self.invalid_escape
+    }
+
+    /// Returns the contextual keyword that the current token spells, if it
+    /// is an identifier name.
+    pub fn contextual_keyword(&self) -> Option<ContextualKeyword> {
+        self.contextual_keyword
+    }
+
+    /// Returns true if there is a line terminator before the current token,
+    /// which matters for automatic semicolon insertion.
+    ///
+    /// Comments are not tokens for this purpose: a line terminator before
+    /// or inside the comments that precede the token counts as well.
+    pub fn has_line_terminator_before(&self) -> bool {
+        self.line_terminator_before
+    }
+
+    /// Returns the source location of the current token.
+    ///
+    /// At the end of the input, the span is an empty span at the end
+    /// position.
+    pub fn span(&self) -> Span {
+        Span::new(self.start, self.position)
+    }
+
+    /// Consumes an identifier name, which is a keyword if it spells a
+    /// reserved word.
+    ///
+    /// An identifier name written with escape sequences, such as `\u0069f`,
+    /// is never a keyword or a contextual keyword, so that the parser can
+    /// reject it where a keyword is required.
     fn consume_identifier(&mut self) -> Option<Token> {
-        let mut ch = self.peek_char();
-        while let Some(c) = ch {
-            if !c.is_alphanumeric() && c != '_' {
+        match self.consume_identifier_name() {
+            Ok(true) => Some(Token::IdentifierName),
+            Ok(false) => {
+                if let Some(token) = Token::from_reserved_word(&self.value) {
+                    return Some(token);
+                }
+                self.contextual_keyword = ContextualKeyword::from_word(&self.value);
+                Some(Token::IdentifierName)
+            }
+            Err(error) => Some(Token::Error(error)),
+        }
+    }
+
+    /// Consumes a private identifier, such as `#a`, which is the name of a
+    /// private class member.
+    fn consume_private_identifier(&mut self) -> Option<Token> {
+        match self.peek_nth_char(1) {
+            Some(ch) if is_identifier_start(ch) || ch == '\\' => {
                 self.consume_char();
-                break;
+                match self.consume_identifier_name() {
+                    Ok(_) => Some(Token::PrivateIdentifier),
+                    Err(error) => Some(Token::Error(error)),
+                }
             }
-            self.consume_char();
-            ch = self.peek_char();
+            _ => self.consume_char_as(Token::Error(TokenError::InvalidCharacter('#'))),
+        }
+    }
+
+    /// Consumes an identifier name and appends it to the cooked value with
+    /// `\uXXXX` and `\u{X...}` escape sequences decoded. Returns true if the
+    /// name contains escape sequences.
+    ///
+    /// An escape sequence that is malformed or that denotes a character not
+    /// allowed in the identifier does not stop the scanning, so that the
+    /// whole identifier name becomes a single error token.
+    fn consume_identifier_name(&mut self) -> Result<bool, TokenError> {
+        let mut escaped = false;
+        let mut error = None;
+        let mut start = true;
+        loop {
+            let allowed = if start {
+                is_identifier_start
+            } else {
+                is_identifier_part
+            };
+            match self.peek_char() {
+                Some('\\') => {
+                    self.consume_char();
+                    escaped = true;
+                    let ch = match self.peek_char() {
+                        Some('u') => {
+                            self.consume_char();
+                            self.consume_unicode_escape()
+                        }
+                        _ => Err(TokenError::InvalidEscapeSequence),
+                    };
+                    match ch {
+                        Ok(ch) if allowed(ch) => self.value.push(ch),
+                        _ => {
+                            error.get_or_insert(TokenError::InvalidEscapeSequence);
+                        }
+                    }
+                }
+                Some(ch) if allowed(ch) => self.consume_value_char(),
+                _ => break,
+            }
+            start = false;
         }
-        match self.slice() {
-            "const" => Some(Token::ConstKeyword),
-            "import" => Some(Token::ImportKeyword),
-            "let" => Some(Token::LetKeyword),
-            "var" => Some(Token::VarKeyword),
-            _ => Some(Token::IdentifierName),
+        match error {
+            Some(error) => Err(error),
+            None => Ok(escaped),
         }
     }
 
+    /// Consumes a numeric literal, which starts with a decimal digit or with
+    /// a period followed by a decimal digit.
+    ///
+    /// Like with string literals, a malformed numeric literal is scanned to
+    /// its end, so that the whole literal becomes a single error token.
     fn consume_numeric_literal(&mut self) -> Option<Token> {
-        // FIXME: decimals and other fancy numeric literals are not supported.
-        let mut ch = self.peek_char();
-        while let Some(c) = ch {
-            if !c.is_numeric() && c != '_' {
-                self.consume_char();
+        let mut error = None;
+        let mut integer = true;
+        let radix = match (self.peek_char(), self.peek_nth_char(1)) {
+            (Some('0'), Some('x' | 'X')) => 16,
+            (Some('0'), Some('o' | 'O')) => 8,
+            (Some('0'), Some('b' | 'B')) => 2,
+            _ => 10,
+        };
+        if radix != 10 {
+            self.consume_value_char();
+            self.consume_value_char();
+            if !self.consume_digits(radix, &mut error) {
+                error.get_or_insert(TokenError::DigitExpected);
+            }
+        } else if self.peek_char() == Some('0')
+            && matches!(self.peek_nth_char(1), Some('0'..='9' | '_'))
+        {
+            // A legacy octal literal, such as `017`, or a decimal literal
+            // with a leading zero, such as `08`. Neither allows separators
+            // or the BigInt suffix.
+            integer = false;
+            let mut octal = true;
+            self.consume_value_char();
+            loop {
+                match self.peek_char() {
+                    Some(ch @ '0'..='9') => {
+                        octal &= ch < '8';
+                        self.consume_value_char();
+                    }
+                    Some('_') => {
+                        self.consume_char();
+                        error.get_or_insert(TokenError::NumericSeparatorNotAllowed);
+                    }
+                    _ => break,
+                }
+            }
+            if !octal {
+                self.consume_fraction_and_exponent(&mut error);
+            }
+        } else {
+            if self.peek_char() != Some('.') {
+                self.consume_digits(10, &mut error);
+            }
+            integer = !self.consume_fraction_and_exponent(&mut error);
+        }
+        let mut token = Token::NumericLiteral;
+        if integer && self.peek_char() == Some('n') {
+            self.consume_char();
+            token = Token::BigIntLiteral;
+        }
+        // The source character immediately following a numeric literal
+        // must not be an identifier start or a digit.
+        match self.peek_char() {
+            Some(ch) if ch.is_ascii_digit() => {
+                error.get_or_insert(TokenError::DigitExpected);
+            }
+            Some(ch) if is_identifier_start(ch) || ch == '\\' => {
+                error.get_or_insert(TokenError::IdentifierAfterNumericLiteral);
+            }
+            _ => {}
+        }
+        while let Some(ch) = self.peek_char() {
+            if !is_identifier_part(ch) {
                 break;
             }
             self.consume_char();
-            ch = self.peek_char();
         }
-        Some(Token::NumericLiteral)
+        match error {
+            Some(error) => Some(Token::Error(error)),
+            None => Some(token),
+        }
     }
 
-    fn consume_double_quote_string_literal(&mut self) -> Option<Token> {
-        let mut prev = self.consume_next_char();
-        while let Some(ch) = self.consume_next_char() {
-            if prev != Some('\\') && ch == '\"' {
-                break;
+    /// Consumes the optional fraction and exponent parts of a decimal
+    /// literal, and returns true if there were any.
+    fn consume_fraction_and_exponent(&mut self, error: &mut Option<TokenError>) -> bool {
+        let mut consumed = false;
+        if self.peek_char() == Some('.') {
+            self.consume_value_char();
+            self.consume_digits(10, error);
+            consumed = true;
+        }
+        if let Some('e' | 'E') = self.peek_char() {
+            self.consume_value_char();
+            if let Some('+' | '-') = self.peek_char() {
+                self.consume_value_char();
+            }
+            if !self.consume_digits(10, error) {
+                error.get_or_insert(TokenError::DigitExpected);
             }
-            prev = Some(ch);
+            consumed = true;
         }
-        Some(Token::StringLiteral)
+        consumed
+    }
+
+    /// Consumes digits of the given radix, which may be separated by single
+    /// numeric separators, and returns true if there were any digits.
+    fn consume_digits(&mut self, radix: u32, error: &mut Option<TokenError>) -> bool {
+        let mut digits = false;
+        let mut separator = false;
+        loop {
+            match self.peek_char() {
+                Some('_') => {
+                    self.consume_char();
+                    if separator {
+                        error.get_or_insert(TokenError::ConsecutiveNumericSeparators);
+                    } else if !digits {
+                        error.get_or_insert(TokenError::NumericSeparatorNotAllowed);
+                    }
+                    separator = true;
+                }
+                Some(ch) if ch.is_digit(radix) => {
+                    self.consume_value_char();
+                    digits = true;
+                    separator = false;
+                }
+                _ => break,
+            }
+        }
+        if separator {
+            error.get_or_insert(TokenError::NumericSeparatorNotAllowed);
+        }
+        digits
+    }
+
+    fn consume_double_quote_string_literal(&mut self) -> Option<Token> {
+        self.consume_string_literal('"')
     }
 
     fn consume_single_quote_string_literal(&mut self) -> Option<Token> {
-        let mut prev = self.consume_next_char();
-        while let Some(ch) = self.consume_next_char() {
-            if prev != Some('\\') && ch == '\'' {
-                break;
+        self.consume_string_literal('\'')
+    }
+
+    /// Consumes a string literal delimited by `quote`, decoding its value.
+    ///
+    /// A malformed escape sequence does not stop the scanning, so that the
+    /// whole string literal becomes a single error token.
+    fn consume_string_literal(&mut self, quote: char) -> Option<Token> {
+        self.consume_char();
+        let mut error = None;
+        loop {
+            match self.peek_char() {
+                Some(ch) if ch == quote => {
+                    self.consume_char();
+                    break;
+                }
+                // Only line continuations and the LS and PS characters may
+                // span lines in a string literal.
+                None | Some('\n') | Some('\r') => {
+                    return Some(Token::Error(TokenError::UnterminatedStringLiteral))
+                }
+                Some('\\') => {
+                    self.consume_char();
+                    if let Err(e) = self.consume_escape_sequence(false) {
+                        error.get_or_insert(e);
+                    }
+                }
+                Some(ch) => {
+                    self.consume_char();
+                    self.value.push(ch);
+                }
             }
-            prev = Some(ch);
         }
-        Some(Token::StringLiteral)
+        match error {
+            Some(error) => Some(Token::Error(error)),
+            None => Some(Token::StringLiteral),
+        }
     }
 
+    /// Consumes a template or a part of one, starting from the opening
+    /// backtick or, if `substitution_end` is true, from the `}` that ends a
+    /// substitution.
+    fn consume_template(&mut self, substitution_end: bool) -> Option<Token> {
+        self.consume_char();
+        loop {
+            match self.peek_char() {
+                Some('`') => {
+                    self.consume_char();
+                    if !substitution_end {
+                        return Some(Token::TemplateLiteral);
+                    }
+                    self.template_braces.pop();
+                    return Some(Token::TemplateTail);
+                }
+                Some('$') if self.peek_nth_char(1) == Some('{') => {
+                    self.consume_char();
+                    self.consume_char();
+                    if substitution_end {
+                        return Some(Token::TemplateMiddle);
+                    }
+                    self.template_braces.push(0);
+                    return Some(Token::TemplateHead);
+                }
+                None => {
+                    if substitution_end {
+                        self.template_braces.pop();
+                    }
+                    return Some(Token::Error(TokenError::UnterminatedTemplateLiteral));
+                }
+                Some('\\') => {
+                    self.consume_char();
+                    if self.consume_escape_sequence(true).is_err() {
+                        self.invalid_escape = true;
+                    }
+                }
+                // Line terminators are normalized to LF in the cooked value.
+                Some('\r') => {
+                    self.consume_char();
+                    if self.peek_char() == Some('\n') {
+                        self.consume_char();
+                    }
+                    self.value.push('\n');
+                }
+                Some(ch) => {
+                    self.consume_char();
+                    self.value.push(ch);
+                }
+            }
+        }
+    }
+
+    /// Consumes an escape sequence after a backslash and appends the
+    /// character it denotes to the cooked value.
+    ///
+    /// Templates do not allow legacy octal escape sequences or `\8` and `\9`.
+    fn consume_escape_sequence(&mut self, template: bool) -> Result<(), TokenError> {
+        let ch = match self.consume_next_char() {
+            Some(ch) => ch,
+            None => return Err(TokenError::UnterminatedStringLiteral),
+        };
+        let cooked = match ch {
+            // A line continuation contributes no characters.
+            '\r' => {
+                if self.peek_char() == Some('\n') {
+                    self.consume_char();
+                }
+                return Ok(());
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => return Ok(()),
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\u{b}',
+            '0' if template => match self.peek_char() {
+                Some('0'..='9') => return Err(TokenError::InvalidEscapeSequence),
+                _ => '\0',
+            },
+            '1'..='9' if template => return Err(TokenError::InvalidEscapeSequence),
+            '0'..='7' => self.consume_legacy_octal_escape(ch),
+            'x' => {
+                let value = self
+                    .consume_hex_digits(2)
+                    .ok_or(TokenError::InvalidEscapeSequence)?;
+                char::from_u32(value).ok_or(TokenError::InvalidEscapeSequence)?
+            }
+            'u' => self.consume_unicode_escape()?,
+            ch => ch,
+        };
+        self.value.push(cooked);
+        Ok(())
+    }
+
+    /// Consumes the rest of a `\0` escape or a legacy octal escape sequence
+    /// such as `\101`, whose first digit is `first`.
+    fn consume_legacy_octal_escape(&mut self, first: char) -> char {
+        // Octal escapes are at most three digits long and at most `\377`.
+        let max_len = if first <= '3' { 3 } else { 2 };
+        let mut value = first.to_digit(8).unwrap_or_default();
+        for _ in 1..max_len {
+            match self.peek_char().and_then(|ch| ch.to_digit(8)) {
+                Some(digit) => {
+                    self.consume_char();
+                    value = value * 8 + digit;
+                }
+                None => break,
+            }
+        }
+        char::from_u32(value).unwrap_or(char::REPLACEMENT_CHARACTER)
+    }
+
+    /// Consumes the rest of a `\uXXXX` or `\u{X...}` escape sequence.
+    ///
+    /// A surrogate pair written as two escape sequences, such as
+    /// `\uD83D\uDE00`, is decoded as a single character. Unpaired surrogates
+    /// cannot be represented in a Rust string and are decoded as U+FFFD.
+    fn consume_unicode_escape(&mut self) -> Result<char, TokenError> {
+        let value = if self.peek_char() == Some('{') {
+            self.consume_char();
+            let mut value: u32 = 0;
+            let mut digits = 0;
+            while let Some(digit) = self.peek_char().and_then(|ch| ch.to_digit(16)) {
+                self.consume_char();
+                value = value.saturating_mul(16).saturating_add(digit);
+                digits += 1;
+            }
+            if digits == 0 || value > 0x10FFFF || self.peek_char() != Some('}') {
+                return Err(TokenError::InvalidEscapeSequence);
+            }
+            self.consume_char();
+            value
+        } else {
+            self.consume_hex_digits(4)
+                .ok_or(TokenError::InvalidEscapeSequence)?
+        };
+        if (0xD800..0xDC00).contains(&value) && self.peek_low_surrogate_escape() {
+            // Consume the `\u` and four hex digits of the low surrogate.
+            self.consume_char();
+            self.consume_char();
+            let low = self.consume_hex_digits(4).unwrap_or_default();
+            let value = 0x10000 + ((value - 0xD800) << 10) + (low - 0xDC00);
+            return Ok(char::from_u32(value).unwrap_or(char::REPLACEMENT_CHARACTER));
+        }
+        Ok(char::from_u32(value).unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    /// Returns true if the next characters are a `\uXXXX` escape sequence of
+    /// a low surrogate.
+    fn peek_low_surrogate_escape(&mut self) -> bool {
+        if self.peek_char() != Some('\\') || self.peek_nth_char(1) != Some('u') {
+            return false;
+        }
+        let mut value = 0;
+        for n in 2..6 {
+            match self.peek_nth_char(n).and_then(|ch| ch.to_digit(16)) {
+                Some(digit) => value = value * 16 + digit,
+                None => return false,
+            }
+        }
+        (0xDC00..0xE000).contains(&value)
+    }
+
+    /// Consumes exactly `count` hex digits and returns their value, or
+    /// returns `None` if there are fewer hex digits.
+    fn consume_hex_digits(&mut self, count: usize) -> Option<u32> {
+        let mut value = 0;
+        for _ in 0..count {
+            let digit = self.peek_char()?.to_digit(16)?;
+            self.consume_char();
+            value = value * 16 + digit;
+        }
+        Some(value)
+    }
+
+    /// Consumes the rest of a single-line comment after the opening `//`,
+    /// up to but not including the line terminator that ends it.
     fn consume_single_line_comment(&mut self) -> Option<Token> {
         let mut ch = self.peek_char();
         while let Some(c) = ch {
-            if c == '\n' {
+            if is_line_terminator(c) {
                 break;
             }
             self.consume_char();
@@ -329,6 +1113,24 @@ impl<'a, R: BufRead> Tokenizer<'a, R> {
         Some(Token::SingleLineComment)
     }
 
+    /// Consumes the rest of a multi-line comment after the opening `/*`.
+    ///
+    /// A multi-line comment that contains a line terminator is itself a
+    /// line terminator for automatic semicolon insertion.
+    fn consume_multi_line_comment(&mut self) -> Option<Token> {
+        loop {
+            match self.peek_char() {
+                Some('*') if self.peek_nth_char(1) == Some('/') => {
+                    self.consume_char();
+                    self.consume_char();
+                    return Some(Token::MultiLineComment);
+                }
+                Some(_) => self.consume_char(),
+                None => return Some(Token::Error(TokenError::UnterminatedComment)),
+            }
+        }
+    }
+
     fn consume_char_as(&mut self, token: Token) -> Option<Token> {
         self.consume_char();
         Some(token)
@@ -340,38 +1142,93 @@ impl<'a, R: BufRead> Tokenizer<'a, R> {
     }
 
     fn consume_char(&mut self) {
-        // If we have a lookahead, consume it; otherwise consume from the
-        // character stream.
-        if let Some(ch) = self.lookaheads.pop_front() {
-            self.slice.push(ch);
-        } else if let Some(Ok(ch)) = self.chars.next() {
+        if let Some(ch) = self.advance_char() {
             self.slice.push(ch);
         }
     }
 
-    fn consume_next_char(&mut self) -> Option<char> {
-        let ch = self.next_char();
-        self.consume_char();
-        ch
+    fn skip_char(&mut self) {
+        self.advance_char();
     }
 
-    fn next_char(&mut self) -> Option<char> {
-        if let Some(ch) = self.lookaheads.front() {
-            Some(*ch)
+    /// Advances past the next character, updating the current position.
+    fn advance_char(&mut self) -> Option<char> {
+        let ch = self.peek_char()?;
+        self.lookaheads.pop_front();
+        self.position.offset += ch.len_utf8();
+        // A CR LF sequence is a single line terminator, so the line is
+        // advanced only once, at the LF.
+        let is_line_terminator = match ch {
+            '\n' | '\u{2028}' | '\u{2029}' => true,
+            '\r' => self.peek_char() != Some('\n'),
+            _ => false,
+        };
+        if is_line_terminator {
+            self.position.line += 1;
+            self.position.column = 0;
         } else {
-            self.peek_char()
+            self.position.column += 1;
         }
+        Some(ch)
     }
 
+    /// Consumes the next character as part of both the slice and the cooked
+    /// value of the current token.
+    fn consume_value_char(&mut self) {
+        if let Some(ch) = self.consume_next_char() {
+            self.value.push(ch);
+        }
+    }
+
+    fn consume_next_char(&mut self) -> Option<char> {
+        let ch = self.peek_char();
+        self.consume_char();
+        ch
+    }
+
+    /// Returns the next character without consuming it.
     fn peek_char(&mut self) -> Option<char> {
-        // Read from the character stream and insert to the vector of
-        // lookaheads if we read something.
-        match self.chars.next() {
-            Some(Ok(lookahead)) => {
-                self.lookaheads.push_back(lookahead);
-                Some(lookahead)
+        self.peek_nth_char(0)
+    }
+
+    /// Returns the `n`th character after the next one without consuming it.
+    fn peek_nth_char(&mut self, n: usize) -> Option<char> {
+        // Read from the character stream into the vector of lookaheads
+        // until we have enough characters buffered.
+        while self.lookaheads.len() <= n {
+            match self.chars.next() {
+                Some(Ok(lookahead)) => self.lookaheads.push_back(lookahead),
+                _ => return None,
             }
-            _ => None,
         }
+        self.lookaheads.get(n).copied()
     }
 }
+
+/// Returns true if `ch` can start an identifier, that is, if it has the
+/// Unicode ID_Start property or is `$` or `_`.
+pub(crate) fn is_identifier_start(ch: char) -> bool {
+    ch == '$' || ch == '_' || ch.is_xid_start()
+}
+
+/// Returns true if `ch` can be part of an identifier after its first
+/// character, that is, if it has the Unicode ID_Continue property or is `$`,
+/// ZWNJ or ZWJ.
+pub(crate) fn is_identifier_part(ch: char) -> bool {
+    matches!(ch, '$' | '\u{200C}' | '\u{200D}') || ch.is_xid_continue()
+}
+
+/// Returns true if `ch` is a whitespace character: TAB, VT, FF, SP, NBSP,
+/// ZWNBSP, which is also the byte order mark, or a space separator (Zs).
+fn is_whitespace(ch: char) -> bool {
+    matches!(
+        ch,
+        '\t' | '\u{b}' | '\u{c}' | ' ' | '\u{a0}' | '\u{feff}' | '\u{1680}' | '\u{2000}'
+            ..='\u{200a}' | '\u{202f}' | '\u{205f}' | '\u{3000}'
+    )
+}
+
+/// Returns true if `ch` is a line terminator.
+fn is_line_terminator(ch: char) -> bool {
+    matches!(ch, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}